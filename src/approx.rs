@@ -0,0 +1,144 @@
+//! Leading and trailing digits of F(n) for indices too large to compute.
+//!
+//! For large n, F(n) = round(φ^n/√5), so log10 F(n) = n·log10 φ − log10 √5.
+//! The integer part of that gives the decimal exponent and 10^(fractional
+//! part) the leading digits. Both need log10 φ to more places than n has
+//! digits, so the constants are evaluated in fixed point with big integers
+//! rather than with `f64`.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::{deadline::Deadline, fib};
+
+/// Leading digits, decimal exponent and trailing digits of |F(n)|.
+pub struct Approximation {
+    /// The first `leading` significant digits, truncated, or all of them if
+    /// F(n) is shorter.
+    pub leading: String,
+    /// |F(n)| ≈ d.ddd… × 10^exponent, so F(n) has exponent + 1 digits.
+    pub exponent: u64,
+    /// |F(n)| mod 10^k, zero-padded to k digits.
+    pub last: String,
+}
+
+/// Guard digits beyond those needed for the index and the leading digits.
+const GUARD_DIGITS: u32 = 15;
+
+/// Approximates |F(n)| for n ≥ 2. `last` must be at most 19 so that
+/// 10^last fits in a `u64`.
+pub fn approximate(n: u64, leading: u32, last: u32) -> Approximation {
+    // n·log10 φ must be accurate to `leading` places after the point, and n
+    // has up to 20 digits.
+    let scale_digits = 20 + leading + GUARD_DIGITS;
+    let one = BigUint::from(10u32).pow(scale_digits);
+
+    let ln2 = atanh_reciprocal(&one, 3u32) * 2u32;
+    let ln10 = ln2.clone() * 3u32 + atanh_reciprocal(&one, 9u32) * 2u32;
+    // ln φ = 2·atanh((φ − 1)/(φ + 1)) and (φ − 1)/(φ + 1) = 1/φ³ = √5 − 2.
+    let sqrt5 = (&one * &one * 5u32).sqrt();
+    let ln_phi = atanh(&one, &(sqrt5 - &one * 2u32)) * 2u32;
+
+    let log10_phi = &ln_phi * &one / &ln10;
+    // log10 √5 = (1 − log10 2)/2.
+    let log10_sqrt5 = (&one - &ln2 * &one / &ln10) / 2u32;
+
+    let log10_f = log10_phi * n - log10_sqrt5;
+    let exponent = (&log10_f / &one).to_string().parse().unwrap_or(u64::MAX);
+    let fraction = log10_f % &one;
+    let mantissa = exp(&one, &(fraction * &ln10 / &one));
+    let leading = leading.min(exponent.saturating_add(1).try_into().unwrap_or(u32::MAX));
+    let leading = mantissa.to_string()[..leading as usize].to_string();
+
+    Approximation {
+        leading,
+        exponent,
+        last: last_digits(n, last),
+    }
+}
+
+/// Returns the same fields computed from the exact value, for indices
+/// small enough that the ψ^n term of Binet's formula still matters.
+pub fn from_exact(value: &BigUint, leading: u32, last: u32) -> Approximation {
+    let text = value.to_string();
+    let start = text.len().saturating_sub(last as usize);
+    Approximation {
+        leading: text.chars().take(leading as usize).collect(),
+        exponent: text.len() as u64 - 1,
+        last: text[start..].to_string(),
+    }
+}
+
+fn last_digits(n: u64, k: u32) -> String {
+    if k == 0 {
+        return String::new();
+    }
+    let modulus = 10u64.pow(k);
+    let value = fib::unbounded(fib::fib_mod(&BigUint::from(n), modulus, &Deadline::none()));
+    format!("{value:0>width$}", width = k as usize)
+}
+
+/// atanh(1/k) = Σ 1/((2j+1)·k^(2j+1)), in fixed point with unit `one`.
+fn atanh_reciprocal(one: &BigUint, k: u32) -> BigUint {
+    let mut power = one / k;
+    let square = k * k;
+    let mut sum = BigUint::zero();
+    let mut j = 0u32;
+    while !power.is_zero() {
+        sum += &power / (2 * j + 1);
+        power /= square;
+        j += 1;
+    }
+    sum
+}
+
+/// atanh(x) = Σ x^(2j+1)/(2j+1) for a fixed-point 0 ≤ x < 1.
+fn atanh(one: &BigUint, x: &BigUint) -> BigUint {
+    let square = x * x / one;
+    let mut power = x.clone();
+    let mut sum = BigUint::zero();
+    let mut j = 0u32;
+    while !power.is_zero() {
+        sum += &power / (2 * j + 1);
+        power = power * &square / one;
+        j += 1;
+    }
+    sum
+}
+
+/// exp(x) = Σ x^j/j! for a fixed-point x ≥ 0.
+fn exp(one: &BigUint, x: &BigUint) -> BigUint {
+    let mut term = one.clone();
+    let mut sum = BigUint::zero();
+    let mut j = 1u32;
+    while !term.is_zero() {
+        sum += &term;
+        term = term * x / one / j;
+        j += 1;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_values() {
+        for n in [100u64, 1_000, 4_321, 20_000] {
+            let exact = from_exact(&fib::fib_pair(n).0, 30, 12);
+            let approx = approximate(n, 30, 12);
+            assert_eq!(approx.exponent, exact.exponent, "n = {n}");
+            assert_eq!(approx.leading, exact.leading, "n = {n}");
+            assert_eq!(approx.last, exact.last, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn huge_index() {
+        // F(10^18) has 208987640249978734 digits, starting 2628978818…
+        let approx = approximate(1_000_000_000_000_000_000, 10, 5);
+        assert_eq!(approx.exponent + 1, 208_987_640_249_978_734);
+        assert_eq!(approx.leading, "2628978818");
+    }
+}
@@ -0,0 +1,254 @@
+//! In-memory cache of computed terms, bounded by an estimate of their size.
+//!
+//! Entries are evicted least recently used first. Recency is a counter
+//! bumped on every access, with a `BTreeMap` from counter to key standing in
+//! for the usual linked list.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponseParts, ResponseParts},
+};
+use num_bigint::BigInt;
+
+/// Fixed per-entry cost on top of the digits: map nodes, the `Arc`, the key.
+const ENTRY_OVERHEAD: usize = 128;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// Sequence name, as registered under `/sequences`.
+    pub sequence: &'static str,
+    pub n: BigInt,
+    pub modulus: Option<u64>,
+}
+
+impl Key {
+    pub fn new(sequence: &'static str, n: impl Into<BigInt>, modulus: Option<u64>) -> Self {
+        Self {
+            sequence,
+            n: n.into(),
+            modulus,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+}
+
+/// Whether a response was served from the cache, reported in the
+/// `Cache-Status` header (RFC 9211).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Hit,
+    Miss,
+    /// The cache is turned off; no header is sent.
+    Disabled,
+}
+
+impl IntoResponseParts for Status {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut parts: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let value = match self {
+            Status::Hit => "argo-rust; hit",
+            Status::Miss => "argo-rust; fwd=miss",
+            Status::Disabled => return Ok(parts),
+        };
+        parts.headers_mut().insert(
+            HeaderName::from_static("cache-status"),
+            HeaderValue::from_static(value),
+        );
+        Ok(parts)
+    }
+}
+
+/// Counters and occupancy, for `/metrics`.
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+pub struct Cache {
+    /// Most bytes of results kept; 0 disables the cache.
+    budget: usize,
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<Key, Entry>,
+    /// Last-use tick to key, oldest first.
+    recency: BTreeMap<u64, Key>,
+    tick: u64,
+    bytes: usize,
+}
+
+struct Entry {
+    value: Arc<BigInt>,
+    tick: u64,
+    size: usize,
+}
+
+impl Cache {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            inner: Mutex::new(Inner::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached value for `key`, counting a hit or a miss.
+    /// Always `None` when the cache is disabled.
+    pub fn get(&self, key: &Key) -> Option<Arc<BigInt>> {
+        if self.budget == 0 {
+            return None;
+        }
+        let value = self.touch(key);
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    /// Stores a freshly computed value, evicting older entries as needed,
+    /// and reports it as a miss. Values larger than the whole budget are
+    /// passed through without being stored.
+    pub fn insert(&self, key: Key, value: BigInt) -> (Arc<BigInt>, Status) {
+        let value = Arc::new(value);
+        if self.budget == 0 {
+            return (value, Status::Disabled);
+        }
+        self.store(key, Arc::clone(&value));
+        (value, Status::Miss)
+    }
+
+    pub fn stats(&self) -> Stats {
+        let inner = self.lock();
+        Stats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: inner.entries.len(),
+            bytes: inner.bytes,
+        }
+    }
+
+    fn touch(&self, key: &Key) -> Option<Arc<BigInt>> {
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        inner.tick += 1;
+        let entry = inner.entries.get_mut(key)?;
+        inner.recency.remove(&entry.tick);
+        entry.tick = inner.tick;
+        inner.recency.insert(entry.tick, key.clone());
+        Some(Arc::clone(&entry.value))
+    }
+
+    fn store(&self, key: Key, value: Arc<BigInt>) {
+        let size = value.bits().div_ceil(8) as usize + key.size() + ENTRY_OVERHEAD;
+        if size > self.budget {
+            return;
+        }
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        if let Some(old) = inner.entries.remove(&key) {
+            inner.recency.remove(&old.tick);
+            inner.bytes -= old.size;
+        }
+        while inner.bytes + size > self.budget {
+            let Some((_, victim)) = inner.recency.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = inner.entries.remove(&victim) {
+                inner.bytes -= evicted.size;
+            }
+        }
+        inner.tick += 1;
+        inner.recency.insert(inner.tick, key.clone());
+        inner.entries.insert(
+            key,
+            Entry {
+                value,
+                tick: inner.tick,
+                size,
+            },
+        );
+        inner.bytes += size;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: i64) -> Key {
+        Key::new("fibonacci", n, None)
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        // Room for three small entries.
+        let cache = Cache::new(3 * (ENTRY_OVERHEAD + 2));
+        for n in 1..=3 {
+            assert!(cache.get(&key(n)).is_none());
+            assert_eq!(cache.insert(key(n), n.into()).1, Status::Miss);
+        }
+        // Touch 1 so that 2 is the oldest when 4 arrives.
+        assert!(cache.get(&key(1)).is_some());
+        cache.insert(key(4), 4.into());
+        assert_eq!(cache.get(&key(1)).as_deref(), Some(&BigInt::from(1)));
+        assert_eq!(cache.get(&key(3)).as_deref(), Some(&BigInt::from(3)));
+        assert!(cache.get(&key(2)).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (3, 4, 3));
+        assert!(stats.bytes <= 3 * (ENTRY_OVERHEAD + 2));
+    }
+
+    #[test]
+    fn keys_include_modulus_and_sequence() {
+        let cache = Cache::new(1 << 20);
+        cache.insert(key(10), 55.into());
+        assert!(cache.get(&Key::new("fibonacci", 10, Some(7))).is_none());
+        assert!(cache.get(&Key::new("lucas", 10, None)).is_none());
+        cache.insert(Key::new("fibonacci", 10, Some(7)), 6.into());
+        assert_eq!(cache.get(&key(10)).as_deref(), Some(&BigInt::from(55)));
+        assert_eq!(
+            cache.get(&Key::new("fibonacci", 10, Some(7))).as_deref(),
+            Some(&BigInt::from(6))
+        );
+    }
+
+    #[test]
+    fn oversized_values_and_disabled_cache_are_not_stored() {
+        let cache = Cache::new(ENTRY_OVERHEAD);
+        let (value, status) = cache.insert(key(1000), BigInt::from(1) << 4096);
+        assert_eq!((value.bits(), status), (4097, Status::Miss));
+        assert_eq!(cache.stats().entries, 0);
+
+        let cache = Cache::new(0);
+        assert_eq!(cache.insert(key(1), 1.into()).1, Status::Disabled);
+        assert!(cache.get(&key(1)).is_none());
+        assert_eq!(cache.stats().misses, 0);
+    }
+}
@@ -0,0 +1,104 @@
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Runtime settings, read once from the environment at startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Largest index magnitude accepted by the Fibonacci endpoints
+    /// (`FIB_MAX_N`).
+    pub max_n: u64,
+    /// Longest decimal value accepted by endpoints that take a Fibonacci
+    /// value rather than an index (`FIB_MAX_VALUE_DIGITS`).
+    pub max_value_digits: usize,
+    /// Longest decimal index accepted by `/fibonacci/{n}/mod/{m}`
+    /// (`FIB_MAX_INDEX_DIGITS`).
+    pub max_index_digits: usize,
+    /// Most terms a single range request may cover (`FIB_MAX_SPAN`).
+    pub max_span: u64,
+    /// Most terms returned per page of a range (`FIB_PAGE_SIZE`).
+    pub page_size: u64,
+    /// Fastest event rate a stream client may request, per second
+    /// (`FIB_STREAM_MAX_RATE`).
+    pub stream_max_rate: u32,
+    /// Most indices accepted by one batch request (`FIB_MAX_BATCH`).
+    pub max_batch: usize,
+    /// Largest `limit` accepted by the Fibonacci primes listing
+    /// (`FIB_PRIMES_MAX_LIMIT`).
+    pub primes_max_limit: u64,
+    /// Most Pisano periods kept in memory (`PISANO_CACHE_SIZE`).
+    pub pisano_cache_size: usize,
+    /// Byte budget of the computed-term cache; 0 disables it
+    /// (`RESULT_CACHE_BYTES`). The default leaves headroom under the
+    /// deployment's 64 MiB memory limit.
+    pub result_cache_bytes: usize,
+    /// Directory of the on-disk term store; unset disables it
+    /// (`FIB_STORE_DIR`).
+    pub store_dir: Option<PathBuf>,
+    /// Most bytes the on-disk store may occupy (`FIB_STORE_MAX_BYTES`).
+    pub store_max_bytes: u64,
+    /// Computations run at once on the blocking pool (`WORKER_THREADS`);
+    /// defaults to the number of CPUs.
+    pub worker_threads: usize,
+    /// Computations allowed to wait for a worker before requests are
+    /// turned away with 503 (`WORKER_QUEUE`).
+    pub worker_queue: usize,
+    /// Default and maximum compute budget per request, in milliseconds
+    /// (`REQUEST_TIMEOUT_MS`).
+    pub request_timeout_ms: u64,
+    /// Largest estimated result size, in bits, accepted by
+    /// `/lucas-sequence` and by `/recurrence` without a modulus
+    /// (`MAX_RESULT_BITS`).
+    pub max_result_bits: u64,
+    /// Largest k accepted by the k-bonacci endpoint (`KBONACCI_MAX_K`).
+    pub kbonacci_max_k: usize,
+    /// Most decimal places returned by the golden-ratio endpoints
+    /// (`PHI_MAX_DIGITS`).
+    pub phi_max_digits: u32,
+    /// Largest order accepted by the recurrence evaluator
+    /// (`RECURRENCE_MAX_ORDER`).
+    pub recurrence_max_order: usize,
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Self {
+            max_n: var("FIB_MAX_N", 100_000),
+            max_value_digits: var("FIB_MAX_VALUE_DIGITS", 1_000),
+            max_index_digits: var("FIB_MAX_INDEX_DIGITS", 1_000),
+            max_span: var("FIB_MAX_SPAN", 10_000),
+            page_size: var("FIB_PAGE_SIZE", 100).max(1),
+            stream_max_rate: var("FIB_STREAM_MAX_RATE", 50).max(1),
+            max_batch: var("FIB_MAX_BATCH", 1_000),
+            primes_max_limit: var("FIB_PRIMES_MAX_LIMIT", 1_000),
+            pisano_cache_size: var("PISANO_CACHE_SIZE", 4_096),
+            result_cache_bytes: var("RESULT_CACHE_BYTES", 8 << 20),
+            store_dir: env::var_os("FIB_STORE_DIR").map(PathBuf::from),
+            store_max_bytes: var("FIB_STORE_MAX_BYTES", 1 << 30),
+            worker_threads: var(
+                "WORKER_THREADS",
+                std::thread::available_parallelism().map_or(1, usize::from),
+            )
+            .max(1),
+            worker_queue: var("WORKER_QUEUE", 64),
+            request_timeout_ms: var("REQUEST_TIMEOUT_MS", 30_000),
+            max_result_bits: var("MAX_RESULT_BITS", 1 << 20),
+            kbonacci_max_k: var("KBONACCI_MAX_K", 10),
+            phi_max_digits: var("PHI_MAX_DIGITS", 10_000),
+            recurrence_max_order: var("RECURRENCE_MAX_ORDER", 64),
+        }
+    }
+}
+
+/// Reads `name` from the environment, falling back to `default` when unset.
+///
+/// A value that is set but does not parse aborts startup rather than being
+/// silently ignored.
+fn var<T: FromStr>(name: &str, default: T) -> T {
+    match env::var(name) {
+        Ok(raw) => raw
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid value for {name}: {raw:?}")),
+        Err(_) => default,
+    }
+}
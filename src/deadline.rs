@@ -0,0 +1,142 @@
+//! Per-request compute budgets.
+//!
+//! A [`Deadline`] travels with a computation, which calls
+//! [`Deadline::check`] between steps and gives up with [`Expired`] once the
+//! deadline has passed or the request has gone away. Cancellation is
+//! cooperative: a step already in progress always finishes.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+};
+use serde::Deserialize;
+
+use crate::{error::ApiError, state::AppState};
+
+/// The request's deadline passed, or its client disconnected.
+#[derive(Debug)]
+pub struct Expired;
+
+#[derive(Clone)]
+pub struct Deadline {
+    at: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Deadline {
+    /// A deadline that never expires, for callers without a budget.
+    pub fn none() -> Self {
+        Self {
+            at: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Some(Instant::now() + timeout),
+            ..Self::none()
+        }
+    }
+
+    pub fn check(&self) -> Result<(), Expired> {
+        let passed = self.at.is_some_and(|at| Instant::now() >= at);
+        if passed || self.cancelled.load(Ordering::Relaxed) {
+            return Err(Expired);
+        }
+        Ok(())
+    }
+
+    pub fn instant(&self) -> Option<Instant> {
+        self.at
+    }
+
+    /// Cancels every clone of this deadline when the returned guard is
+    /// dropped, e.g. along with a handler future whose client went away.
+    /// [`CancelOnDrop::disarm`] keeps it alive once the work is done.
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(Some(Arc::clone(&self.cancelled)))
+    }
+}
+
+pub struct CancelOnDrop(Option<Arc<AtomicBool>>);
+
+impl CancelOnDrop {
+    pub fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(cancelled) = &self.0 {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TimeoutQuery {
+    /// Milliseconds.
+    timeout: Option<u64>,
+}
+
+/// The earliest of the `timeout` query parameter (milliseconds from now),
+/// the `X-Request-Deadline` header (Unix time in milliseconds) and the
+/// server's `REQUEST_TIMEOUT_MS`. Clients can shorten the budget but not
+/// extend it.
+impl FromRequestParts<Arc<AppState>> for Deadline {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let invalid =
+            |message: &str| ApiError::new(StatusCode::BAD_REQUEST, "invalid_timeout", message);
+        let mut timeout = Duration::from_millis(state.config.request_timeout_ms);
+        let Query(query) = Query::<TimeoutQuery>::try_from_uri(&parts.uri)
+            .map_err(|_| invalid("timeout must be a whole number of milliseconds"))?;
+        if let Some(ms) = query.timeout {
+            timeout = timeout.min(Duration::from_millis(ms));
+        }
+        if let Some(header) = parts.headers.get("x-request-deadline") {
+            let at = header
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .ok_or_else(|| invalid("X-Request-Deadline must be a Unix time in milliseconds"))?;
+            let remaining = (UNIX_EPOCH + Duration::from_millis(at))
+                .duration_since(SystemTime::now())
+                .unwrap_or_default();
+            timeout = timeout.min(remaining);
+        }
+        Ok(Self::after(timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_after_timeout_or_cancellation() {
+        assert!(Deadline::none().check().is_ok());
+        assert!(Deadline::after(Duration::ZERO).check().is_err());
+
+        let deadline = Deadline::after(Duration::from_secs(60));
+        let worker = deadline.clone();
+        deadline.cancel_on_drop().disarm();
+        assert!(worker.check().is_ok());
+        drop(deadline.cancel_on_drop());
+        assert!(worker.check().is_err());
+    }
+}
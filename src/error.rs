@@ -0,0 +1,93 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::{deadline::Expired, pool::Saturated};
+
+/// Machine-readable error payload, nested under `"error"` in responses.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// An error returned from a handler as a JSON body with a matching status.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub body: ErrorBody,
+    /// Seconds to send in a `Retry-After` header.
+    pub retry_after: Option<u64>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ErrorBody {
+                code,
+                message: message.into(),
+            },
+            retry_after: None,
+        }
+    }
+
+    pub fn n_out_of_range(n: impl std::fmt::Display, max_n: u64) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "n_out_of_range",
+            format!("n = {n} is outside the configured range of ±{max_n}"),
+        )
+    }
+
+    pub fn result_too_large(bits: f64, max_bits: u64) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "result_too_large",
+            format!("the result would have about {bits:.0} bits, more than the configured maximum of {max_bits}"),
+        )
+    }
+}
+
+impl From<Saturated> for ApiError {
+    fn from(_: Saturated) -> Self {
+        Self {
+            retry_after: Some(1),
+            ..Self::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "overloaded",
+                "too many computations in progress; retry shortly",
+            )
+        }
+    }
+}
+
+impl From<Expired> for ApiError {
+    fn from(_: Expired) -> Self {
+        Self::new(
+            StatusCode::GATEWAY_TIMEOUT,
+            "deadline_exceeded",
+            "the computation did not finish before the request deadline",
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorEnvelope<'a> {
+    error: &'a ErrorBody,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Json(ErrorEnvelope { error: &self.body })).into_response();
+        if let Some(seconds) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, seconds.into());
+        }
+        response
+    }
+}
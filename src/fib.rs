@@ -0,0 +1,277 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::deadline::{Deadline, Expired};
+
+/// ln φ, for estimating an index from a value's magnitude.
+pub const LN_PHI: f64 = 0.481_211_825_059_603_4;
+
+/// Returns F(n) exactly, for negative indices too.
+///
+/// The sequence extends backwards as F(-n) = (-1)^(n+1) F(n), so
+/// F(-1) = 1, F(-2) = -1, F(-3) = 2, … Request handlers use
+/// [`fib_within`]; this unbounded form is for tests.
+#[cfg(test)]
+pub fn fib(n: i64) -> BigInt {
+    unbounded(fib_within(n, &Deadline::none()))
+}
+
+/// [`fib`], giving up between doubling steps once `deadline` expires.
+pub fn fib_within(n: i64, deadline: &Deadline) -> Result<BigInt, Expired> {
+    let value = BigInt::from(fib_pair_within(n.unsigned_abs(), deadline)?.0);
+    Ok(if n < 0 && n % 2 == 0 { -value } else { value })
+}
+
+/// Returns (F(n), F(n+1)).
+///
+/// Uses fast doubling, so the cost is O(log n) big-integer multiplications:
+/// F(2k) = F(k) * (2F(k+1) - F(k)) and F(2k+1) = F(k)^2 + F(k+1)^2.
+pub fn fib_pair(n: u64) -> (BigUint, BigUint) {
+    unbounded(fib_pair_within(n, &Deadline::none()))
+}
+
+/// [`fib_pair`], giving up between doubling steps once `deadline` expires.
+/// The last few steps dominate the cost, so that is also where most checks
+/// land.
+pub fn fib_pair_within(n: u64, deadline: &Deadline) -> Result<(BigUint, BigUint), Expired> {
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        deadline.check()?;
+        let c = &a * (&b * 2u32 - &a);
+        let d = &a * &a + &b * &b;
+        (a, b) = if (n >> bit) & 1 == 0 {
+            (c, d)
+        } else {
+            let e = c + &d;
+            (d, e)
+        };
+    }
+    Ok((a, b))
+}
+
+/// Unwraps the result of a computation run under [`Deadline::none`].
+pub fn unbounded<T>(result: Result<T, Expired>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(Expired) => unreachable!("a computation without a deadline expired"),
+    }
+}
+
+/// Returns F(n) mod m for an index of any size, without ever materialising
+/// F(n). Intermediates are reduced mod m, so each doubling step is a handful
+/// of `u128` operations.
+pub fn fib_mod(n: &BigUint, m: u64, deadline: &Deadline) -> Result<u64, Expired> {
+    Ok(fib_mod_pair(n, m, deadline)?.0)
+}
+
+/// Returns (F(n) mod m, F(n+1) mod m), giving up between doubling steps
+/// once `deadline` expires.
+pub fn fib_mod_pair(n: &BigUint, m: u64, deadline: &Deadline) -> Result<(u64, u64), Expired> {
+    let m = u128::from(m);
+    let (mut a, mut b) = (0u128, 1 % m);
+    for bit in (0..n.bits()).rev() {
+        deadline.check()?;
+        let c = a * ((2 * b + m - a) % m) % m;
+        let d = (a * a % m + b * b % m) % m;
+        (a, b) = if n.bit(bit) { (d, (c + d) % m) } else { (c, d) };
+    }
+    Ok((a as u64, b as u64))
+}
+
+/// Returns Σ F(i) for from ≤ i ≤ to, via the telescoping identity
+/// Σ F(i) = F(to+2) − F(from+1), which holds for negative indices too.
+pub fn sum(from: i64, to: i64, deadline: &Deadline) -> Result<BigInt, Expired> {
+    Ok(fib_within(to + 2, deadline)? - fib_within(from + 1, deadline)?)
+}
+
+/// Returns Σ F(i)² for from ≤ i ≤ to, via F(i)² = F(i)F(i+1) − F(i−1)F(i),
+/// which telescopes to F(to)F(to+1) − F(from−1)F(from).
+pub fn sum_of_squares(from: i64, to: i64, deadline: &Deadline) -> Result<BigInt, Expired> {
+    let top = fib_within(to, deadline)? * fib_within(to + 1, deadline)?;
+    Ok(top - fib_within(from - 1, deadline)? * fib_within(from, deadline)?)
+}
+
+/// Returns gcd(F(a), F(b)) = F(gcd(|a|, |b|)).
+pub fn gcd(a: i64, b: i64, deadline: &Deadline) -> Result<(u64, BigUint), Expired> {
+    let (mut x, mut y) = (a.unsigned_abs(), b.unsigned_abs());
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    Ok((x, fib_pair_within(x, deadline)?.0))
+}
+
+/// Returns an index n with F(n) = `value`, or `None` if `value` is not a
+/// Fibonacci number. Positive values get the smallest non-negative index
+/// (so 1 maps to 1, not 2); negative values the index in the negative
+/// half of the sequence.
+///
+/// Membership is the classic test: v is a Fibonacci number iff 5v² + 4 or
+/// 5v² − 4 is a perfect square. The index then follows from F(n) being the
+/// nearest integer to φ^n/√5, i.e. n = round(log_φ(v·√5)).
+pub fn index_of(value: &BigInt) -> Option<i64> {
+    let magnitude = value.magnitude();
+    if magnitude.is_zero() {
+        return Some(0);
+    }
+    let is_square = |x: &BigUint| {
+        let root = x.sqrt();
+        &root * &root == *x
+    };
+    let five_squared = magnitude * magnitude * 5u32;
+    if !is_square(&(&five_squared + 4u32)) && !is_square(&(five_squared - 4u32)) {
+        return None;
+    }
+    if magnitude.is_one() {
+        // 1 = F(1) = F(2) = F(-1), while -1 only appears as F(-2).
+        return Some(if value.sign() == Sign::Minus { -2 } else { 1 });
+    }
+    let n = ((ln(magnitude) + 5f64.sqrt().ln()) / LN_PHI).round() as i64;
+    match value.sign() {
+        // F(-n) is negative exactly when n is even.
+        Sign::Minus => (n % 2 == 0).then_some(-n),
+        _ => Some(n),
+    }
+}
+
+/// Natural logarithm of a non-zero big integer, to `f64` precision.
+pub fn ln(value: &BigUint) -> f64 {
+    let shift = value.bits().saturating_sub(f64::MANTISSA_DIGITS.into());
+    let top = (value >> shift).to_f64().unwrap_or(f64::MAX);
+    top.ln() + shift as f64 * std::f64::consts::LN_2
+}
+
+/// Consecutive terms starting at a given index, yielded as `(n, F(n))`.
+///
+/// Only the starting pair is computed by fast doubling; every later term
+/// costs one big-integer addition.
+pub struct Terms {
+    n: i64,
+    a: BigInt,
+    b: BigInt,
+}
+
+/// Returns [`Terms`] from `from`, giving up while computing the starting
+/// pair once `deadline` expires. Callers check it between terms themselves.
+pub fn terms_within(from: i64, deadline: &Deadline) -> Result<Terms, Expired> {
+    let (a, b) = if from >= 0 {
+        let (a, b) = fib_pair_within(from.unsigned_abs(), deadline)?;
+        (a.into(), b.into())
+    } else {
+        (fib_within(from, deadline)?, fib_within(from + 1, deadline)?)
+    };
+    Ok(Terms { n: from, a, b })
+}
+
+impl Iterator for Terms {
+    type Item = (i64, BigInt);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.n;
+        self.n = n.checked_add(1)?;
+        let next = &self.a + &self.b;
+        let current = std::mem::replace(&mut self.a, std::mem::replace(&mut self.b, next));
+        Some((n, current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib_iterative(n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        let (mut a, mut b) = (0u64, 1u64);
+        for _ in 1..n {
+            (a, b) = (b, a.saturating_add(b));
+        }
+        b
+    }
+
+    #[test]
+    fn matches_iterative_for_small_n() {
+        for n in 0..=93 {
+            assert_eq!(fib(n), BigInt::from(fib_iterative(n as u64)), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn exact_past_u64() {
+        assert_eq!(fib(100).to_string(), "354224848179261915075");
+        let (a, b) = fib_pair(1000);
+        assert_eq!(BigInt::from(b - &a), fib(999));
+    }
+
+    #[test]
+    fn negative_indices_follow_the_recurrence() {
+        assert_eq!(fib(-1), BigInt::from(1));
+        assert_eq!(fib(-2), BigInt::from(-1));
+        assert_eq!(fib(-6), BigInt::from(-8));
+        for n in -200..200 {
+            assert_eq!(fib(n) + fib(n + 1), fib(n + 2), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn fib_mod_matches_fib() {
+        for m in [1, 2, 10, 1_000_000_007, u64::MAX] {
+            for n in 0..=300u64 {
+                let expected = fib_pair(n).0 % m;
+                assert_eq!(
+                    BigUint::from(fib_mod(&n.into(), m, &Deadline::none()).unwrap()),
+                    expected,
+                    "F({n}) mod {m}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn identities_match_direct_computation() {
+        let none = Deadline::none();
+        for from in -15..15 {
+            for to in from..15 {
+                let direct: BigInt = (from..=to).map(fib).sum();
+                assert_eq!(sum(from, to, &none).unwrap(), direct, "sum {from}..={to}");
+                let direct: BigInt = (from..=to).map(|i| fib(i) * fib(i)).sum();
+                assert_eq!(
+                    sum_of_squares(from, to, &none).unwrap(),
+                    direct,
+                    "squares {from}..={to}"
+                );
+            }
+        }
+        for a in -30..30i64 {
+            for b in -30..30i64 {
+                let (mut x, mut y) = (fib(a).magnitude().clone(), fib(b).magnitude().clone());
+                while !y.is_zero() {
+                    (x, y) = (y.clone(), x % y);
+                }
+                assert_eq!(gcd(a, b, &none).unwrap().1, x, "gcd(F({a}), F({b}))");
+            }
+        }
+    }
+
+    #[test]
+    fn index_of_inverts_fib() {
+        for n in (-300..=300).filter(|n| *n != 2) {
+            // F(-n) = F(n) for odd n, which reports the non-negative index.
+            let expected = if n < 0 && n % 2 != 0 { -n } else { n };
+            assert_eq!(index_of(&fib(n)), Some(expected), "n = {n}");
+        }
+        assert_eq!(index_of(&fib(12_345)), Some(12_345));
+        for v in [4, 6, 7, 9, -2, -5, 90] {
+            assert_eq!(index_of(&BigInt::from(v)), None, "v = {v}");
+        }
+        assert_eq!(index_of(&(fib(500) + 1)), None);
+    }
+
+    #[test]
+    fn terms_match_fib() {
+        let terms = |from| terms_within(from, &Deadline::none()).unwrap();
+        for (n, value) in terms(-10).take(20).chain(terms(90).take(20)) {
+            assert_eq!(value, fib(n), "n = {n}");
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Fibonacci universal code for positive `u64`s, packed MSB-first.
+//!
+//! A value's codeword is its Zeckendorf representation written as one bit
+//! per F(2), F(3), … followed by an extra `1`. No Zeckendorf representation
+//! has two adjacent ones, so `11` marks the end of every codeword and a
+//! stream can be split without lengths. The final byte is padded with
+//! zeros, which the decoder ignores.
+
+use std::{fmt, sync::OnceLock};
+
+/// F(2), F(3), …, F(93): every Fibonacci number that fits in a `u64`.
+fn table() -> &'static [u64] {
+    static TABLE: OnceLock<Vec<u64>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let (mut a, mut b) = (1u64, 2u64);
+        let mut table = vec![a];
+        while let Some(next) = a.checked_add(b) {
+            table.push(b);
+            (a, b) = (b, next);
+        }
+        table.push(b);
+        table
+    })
+}
+
+/// Packs codewords into bytes, MSB first.
+#[derive(Default)]
+pub struct Encoder {
+    byte: u8,
+    filled: u8,
+}
+
+impl Encoder {
+    /// Appends the codeword for `value` (which must be non-zero), emitting
+    /// every completed byte into `out`.
+    pub fn push(&mut self, value: u64, out: &mut Vec<u8>) {
+        let table = table();
+        let top = table.partition_point(|f| *f <= value) - 1;
+        let mut bits = [false; 93];
+        let mut remainder = value;
+        for i in (0..=top).rev() {
+            if table[i] <= remainder {
+                remainder -= table[i];
+                bits[i] = true;
+            }
+        }
+        for &bit in &bits[..=top] {
+            self.write(bit, out);
+        }
+        self.write(true, out);
+    }
+
+    /// Flushes the final partial byte, zero-padded.
+    pub fn finish(self, out: &mut Vec<u8>) {
+        if self.filled > 0 {
+            out.push(self.byte << (8 - self.filled));
+        }
+    }
+
+    fn write(&mut self, bit: bool, out: &mut Vec<u8>) {
+        self.byte = (self.byte << 1) | u8::from(bit);
+        self.filled += 1;
+        if self.filled == 8 {
+            out.push(self.byte);
+            (self.byte, self.filled) = (0, 0);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A codeword's value does not fit in a `u64`.
+    Overflow,
+    /// The input ended inside a codeword.
+    Truncated,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => f.write_str("codeword value does not fit in 64 bits"),
+            Self::Truncated => f.write_str("input ends in the middle of a codeword"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Splits a bit stream back into values. Feed it bytes in any chunking.
+#[derive(Default)]
+pub struct Decoder {
+    value: u64,
+    /// Position within the current codeword, i.e. the next bit is F(index+2).
+    index: usize,
+    previous: bool,
+}
+
+impl Decoder {
+    pub fn feed(&mut self, bytes: &[u8], out: &mut Vec<u64>) -> Result<(), DecodeError> {
+        let table = table();
+        for byte in bytes {
+            for shift in (0..8).rev() {
+                let bit = (byte >> shift) & 1 == 1;
+                if bit && self.previous {
+                    out.push(self.value);
+                    *self = Self::default();
+                    continue;
+                }
+                if bit {
+                    let term = table.get(self.index).ok_or(DecodeError::Overflow)?;
+                    self.value = self.value.checked_add(*term).ok_or(DecodeError::Overflow)?;
+                }
+                self.previous = bit;
+                self.index += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the input ended on a codeword boundary, allowing only
+    /// zero padding after the last one.
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if self.value == 0 {
+            Ok(())
+        } else {
+            Err(DecodeError::Truncated)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(values: &[u64]) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        let mut out = Vec::new();
+        for &v in values {
+            encoder.push(v, &mut out);
+        }
+        encoder.finish(&mut out);
+        out
+    }
+
+    #[test]
+    fn known_codewords() {
+        // 1 → 11, 2 → 011, 3 → 0011, 4 → 1011.
+        assert_eq!(encode(&[1]), [0b1100_0000]);
+        assert_eq!(encode(&[1, 2, 3, 4]), [0b1101_1001, 0b1101_1000]);
+    }
+
+    #[test]
+    fn round_trips_in_any_chunking() {
+        let values: Vec<u64> = (1..300)
+            .chain([u64::MAX, 12_200_160_415_121_876_738])
+            .collect();
+        let bytes = encode(&values);
+        for chunk in [1, 3, bytes.len()] {
+            let mut decoder = Decoder::default();
+            let mut out = Vec::new();
+            for piece in bytes.chunks(chunk) {
+                decoder.feed(piece, &mut out).unwrap();
+            }
+            decoder.finish().unwrap();
+            assert_eq!(out, values);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let mut out = Vec::new();
+        let mut decoder = Decoder::default();
+        decoder.feed(&[0b0100_0000], &mut out).unwrap();
+        assert_eq!(decoder.finish(), Err(DecodeError::Truncated));
+        // 93 alternating bits overflow before any terminator.
+        assert_eq!(
+            Decoder::default().feed(&[0b1010_1010; 13], &mut out),
+            Err(DecodeError::Overflow)
+        );
+    }
+}
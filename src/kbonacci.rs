@@ -0,0 +1,97 @@
+//! k-bonacci numbers: each term is the sum of the k before it.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::deadline::{Deadline, Expired};
+
+type Matrix = Vec<Vec<BigUint>>;
+
+/// Returns the nth k-bonacci number for `k >= 1`, seeded with k − 1 zeros
+/// followed by a one (so k = 2 is Fibonacci and k = 3 is OEIS A000073).
+///
+/// Computed as an entry of M^n for the k×k companion matrix M, by binary
+/// exponentiation: O(k³ log n) big-integer multiplications. Gives up
+/// between matrix rows once `deadline` expires.
+pub fn kbonacci(k: usize, n: u64, deadline: &Deadline) -> Result<BigUint, Expired> {
+    // M maps [T(i+k-1), …, T(i)] to [T(i+k), …, T(i+1)]: the first row sums
+    // the window and the sub-diagonal shifts it down.
+    let mut base: Matrix = vec![vec![BigUint::zero(); k]; k];
+    for (i, row) in base.iter_mut().enumerate() {
+        if i == 0 {
+            row.fill(BigUint::one());
+        } else {
+            row[i - 1] = BigUint::one();
+        }
+    }
+    let mut power = identity(k);
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        power = multiply(&power, &power, deadline)?;
+        if (n >> bit) & 1 == 1 {
+            power = multiply(&power, &base, deadline)?;
+        }
+    }
+    // The initial window is [1, 0, …, 0], so T(n) is the bottom-left entry.
+    Ok(power[k - 1][0].clone())
+}
+
+fn identity(k: usize) -> Matrix {
+    (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| {
+                    if i == j {
+                        BigUint::one()
+                    } else {
+                        BigUint::zero()
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply(a: &Matrix, b: &Matrix, deadline: &Deadline) -> Result<Matrix, Expired> {
+    let k = a.len();
+    (0..k)
+        .map(|i| {
+            deadline.check()?;
+            Ok((0..k)
+                .map(|j| (0..k).map(|m| &a[i][m] * &b[m][j]).sum())
+                .collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn matches_direct_summation() {
+        let none = Deadline::none();
+        for k in 1..=6 {
+            let mut terms: Vec<BigUint> = vec![BigUint::zero(); k - 1];
+            terms.push(BigUint::one());
+            while terms.len() < 80 {
+                let next = terms[terms.len() - k..].iter().sum();
+                terms.push(next);
+            }
+            for (n, expected) in terms.iter().enumerate() {
+                assert_eq!(
+                    &kbonacci(k, n as u64, &none).unwrap(),
+                    expected,
+                    "k={k} n={n}"
+                );
+            }
+        }
+        assert_eq!(
+            BigInt::from(kbonacci(2, 500, &none).unwrap()),
+            fib::fib(500)
+        );
+        let past = Deadline::after(std::time::Duration::ZERO);
+        assert!(kbonacci(3, 500, &past).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Lucas numbers and the generalised Lucas sequences U_n(P, Q), V_n(P, Q).
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+
+use crate::{
+    deadline::{Deadline, Expired},
+    fib,
+};
+
+/// Returns the Lucas number L(n), for negative indices too.
+///
+/// Derived from the Fibonacci fast-doubling pair: L(n) = 2F(n+1) − F(n),
+/// and L(−n) = (−1)^n L(n). Gives up between doubling steps once
+/// `deadline` expires.
+pub fn lucas_within(n: i64, deadline: &Deadline) -> Result<BigInt, Expired> {
+    let (a, b) = fib::fib_pair_within(n.unsigned_abs(), deadline)?;
+    let value = BigInt::from(b * 2u32) - BigInt::from(a);
+    Ok(if n < 0 && n % 2 != 0 { -value } else { value })
+}
+
+/// Returns (U_n(P, Q), V_n(P, Q)) for x_{n} = P·x_{n−1} − Q·x_{n−2}, with
+/// U_0 = 0, U_1 = 1, V_0 = 2, V_1 = P.
+///
+/// Fast doubling over (U_k, V_k, Q^k):
+/// U_2k = U_k·V_k, V_2k = V_k² − 2Q^k, and stepping k → k+1 via
+/// U_{k+1} = (P·U_k + V_k)/2, V_{k+1} = (D·U_k + P·V_k)/2 with D = P² − 4Q.
+/// Both halvings are exact because V_k ≡ P·U_k (mod 2). Gives up between
+/// doubling steps once `deadline` expires.
+pub fn lucas_uv(p: i64, q: i64, n: u64, deadline: &Deadline) -> Result<(BigInt, BigInt), Expired> {
+    let (p, q) = (BigInt::from(p), BigInt::from(q));
+    let d = &p * &p - &q * 4;
+    let (mut u, mut v, mut qk) = (BigInt::zero(), BigInt::from(2), BigInt::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        deadline.check()?;
+        (u, v) = (&u * &v, &v * &v - &qk * 2);
+        qk = &qk * &qk;
+        if (n >> bit) & 1 == 1 {
+            (u, v) = ((&p * &u + &v) / 2, (&d * &u + &p * &v) / 2);
+            qk *= &q;
+        }
+    }
+    Ok((u, v))
+}
+
+/// Estimated size in bits of U_n(P, Q) and V_n(P, Q): n·log₂ max(|P| + |Q|, 2).
+///
+/// A root r of x² − Px + Q with |r| > 1 satisfies |r|² ≤ |P|·|r| + |Q|, so
+/// |r| < |P| + |Q|; the terms are then within a factor of n of |r|^n.
+pub fn uv_bits(p: i64, q: i64, n: u64) -> f64 {
+    let base = (p.unsigned_abs() as f64 + q.unsigned_abs() as f64).max(2.0);
+    n as f64 * base.log2()
+}
+
+/// Returns (U_k, V_k, Q^k) mod m for an odd modulus m > 1, using the same
+/// doubling steps as [`lucas_uv`]. The halvings become multiplication by
+/// the inverse of 2, which exists because m is odd.
+pub fn lucas_uv_mod(
+    p: i64,
+    q: i64,
+    k: &BigUint,
+    m: &BigUint,
+    deadline: &Deadline,
+) -> Result<(BigUint, BigUint, BigUint), Expired> {
+    let modulus = BigInt::from(m.clone());
+    let residue = |x: i64| {
+        let r = BigInt::from(x) % &modulus;
+        let r = if r.sign() == Sign::Minus {
+            r + &modulus
+        } else {
+            r
+        };
+        r.magnitude().clone()
+    };
+    let half = |x: BigUint| if x.bit(0) { (x + m) >> 1 } else { x >> 1 };
+    let (p, q) = (residue(p), residue(q));
+    let d = (&p * &p + (m - &q) * 4u32) % m;
+    let (mut u, mut v, mut qk) = (BigUint::zero(), BigUint::from(2u32) % m, BigUint::one() % m);
+    for bit in (0..k.bits()).rev() {
+        deadline.check()?;
+        u = &u * &v % m;
+        v = (&v * &v + (m - &qk) * 2u32) % m;
+        qk = &qk * &qk % m;
+        if k.bit(bit) {
+            (u, v) = (half((&p * &u + &v) % m), half((&d * &u + &p * &v) % m));
+            qk = &qk * &q % m;
+        }
+    }
+    Ok((u, v, qk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uv(p: i64, q: i64, n: u64) -> (BigInt, BigInt) {
+        lucas_uv(p, q, n, &Deadline::none()).unwrap()
+    }
+
+    fn lucas(n: i64) -> BigInt {
+        fib::unbounded(lucas_within(n, &Deadline::none()))
+    }
+
+    #[test]
+    fn lucas_numbers() {
+        let expected = [2, 1, 3, 4, 7, 11, 18, 29];
+        for (n, l) in expected.into_iter().enumerate() {
+            assert_eq!(lucas(n as i64), BigInt::from(l));
+        }
+        assert_eq!(lucas(-5), BigInt::from(-11));
+        assert_eq!(lucas(-6), BigInt::from(18));
+    }
+
+    #[test]
+    fn uv_matches_recurrence() {
+        for (p, q) in [(1, -1), (3, 2), (-2, 5), (4, 4), (0, -3)] {
+            let (mut u, mut v) = (
+                (BigInt::zero(), BigInt::one()),
+                (BigInt::from(2), BigInt::from(p)),
+            );
+            for n in 0..60 {
+                assert_eq!(uv(p, q, n), (u.0.clone(), v.0.clone()), "P={p} Q={q} n={n}");
+                u = (u.1.clone(), &u.1 * p - &u.0 * q);
+                v = (v.1.clone(), &v.1 * p - &v.0 * q);
+            }
+        }
+        assert_eq!(uv(1, -1, 90).0, fib::fib(90));
+        for (p, q) in [(1, -1), (3, 2), (-2, 5)] {
+            for n in 0..60u64 {
+                let (u, v) = uv(p, q, n);
+                let m = BigInt::from(1_000_003);
+                let reduce = |x: BigInt| (((x % &m) + &m) % &m).magnitude().clone();
+                let (um, vm, _) =
+                    lucas_uv_mod(p, q, &BigUint::from(n), m.magnitude(), &Deadline::none())
+                        .unwrap();
+                assert_eq!((um, vm), (reduce(u), reduce(v)), "P={p} Q={q} n={n}");
+            }
+        }
+        assert_eq!(uv(1, -1, 90).1, lucas(90));
+        assert_eq!(uv_bits(3, 2, 10), 10.0 * 5f64.log2());
+        assert_eq!(uv_bits(1, 0, 10), 10.0);
+        for (p, q) in [(2, -2), (1, -1), (-4, 3), (0, -5), (3, 3)] {
+            for n in 1..80u64 {
+                let (u, v) = uv(p, q, n);
+                let slack = (n as f64).log2() + 1.0;
+                let bound = uv_bits(p, q, n) + slack;
+                assert!(u.bits().max(v.bits()) as f64 <= bound, "P={p} Q={q} n={n}");
+            }
+        }
+    }
+}
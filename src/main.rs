@@ -1,41 +1,33 @@
-use axum::{extract::Path, routing::get, Json, Router};
-use serde::Serialize;
+mod approx;
+mod cache;
+mod config;
+mod deadline;
+mod error;
+mod factor;
+mod fib;
+mod fibcode;
+mod kbonacci;
+mod lucas;
+mod phi;
+mod pisano;
+mod pool;
+mod primes;
+mod recurrence;
+mod routes;
+mod sequences;
+mod state;
+mod store;
+mod zeckendorf;
 
-#[derive(Serialize)]
-struct HelloResponse {
-    message: String,
-}
-
-#[derive(Serialize)]
-struct FibResponse {
-    n: u64,
-    result: u64,
-}
+use std::sync::Arc;
 
-async fn hello() -> Json<HelloResponse> {
-    Json(HelloResponse {
-        message: "Hello Dennis!".to_string(),
-    })
-}
-
-async fn fibonacci(Path(n): Path<u64>) -> Json<FibResponse> {
-    let result = if n == 0 {
-        0
-    } else {
-        let (mut a, mut b) = (0u64, 1u64);
-        for _ in 1..n {
-            (a, b) = (b, a.saturating_add(b));
-        }
-        b
-    };
-    Json(FibResponse { n, result })
-}
+use config::Config;
+use state::AppState;
 
 #[tokio::main]
 async fn main() {
-    let app = Router::new()
-        .route("/hello", get(hello))
-        .route("/fibonacci/{n}", get(fibonacci));
+    let state = Arc::new(AppState::new(Config::from_env()));
+    let app = routes::router(state);
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
     println!("Listening on 0.0.0.0:8080");
     axum::serve(listener, app).await.unwrap();
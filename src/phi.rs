@@ -0,0 +1,74 @@
+//! The golden ratio φ = (1 + √5)/2 and its Fibonacci convergents.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// Returns φ truncated (not rounded) to `digits` decimal places.
+///
+/// floor(φ·10^d) = floor((10^d + isqrt(5·10^2d)) / 2): truncating √5 first
+/// cannot change the result because the outer floor only halves.
+pub fn phi(digits: u32) -> String {
+    let scale = BigUint::from(10u32).pow(digits);
+    let root = (&scale * &scale * 5u32).sqrt();
+    point(&((scale + root) >> 1), digits)
+}
+
+/// Returns num/den truncated to `digits` decimal places.
+pub fn decimal(num: &BigUint, den: &BigUint, digits: u32) -> String {
+    point(&(num * BigUint::from(10u32).pow(digits) / den), digits)
+}
+
+/// Formats 1/den rounded up to three significant figures in scientific
+/// notation, e.g. `1.24e-21`, so the printed value is still an upper bound.
+pub fn reciprocal_upper_bound(den: &BigUint) -> String {
+    if den.is_one() {
+        return "1.00e0".to_string();
+    }
+    // 1/den lies in (10^-k, 10^(1-k)] for a k-digit den; scaling by
+    // 10^(k+2) yields a three-digit mantissa.
+    let k = den.to_string().len() as i64;
+    let scaled = BigUint::from(10u32).pow((k + 2) as u32);
+    let (quotient, remainder) = (&scaled / den, &scaled % den);
+    let mut mantissa = quotient + u32::from(!remainder.is_zero());
+    let mut exponent = -k;
+    if mantissa == BigUint::from(1000u32) {
+        mantissa = BigUint::from(100u32);
+        exponent += 1;
+    }
+    let m = mantissa.to_string();
+    format!("{}.{}e{exponent}", &m[..1], &m[1..])
+}
+
+/// Inserts a decimal point `digits` places from the right of `scaled`.
+fn point(scaled: &BigUint, digits: u32) -> String {
+    let digits = digits as usize;
+    let raw = format!("{scaled:0>width$}", width = digits + 1);
+    let (whole, fraction) = raw.split_at(raw.len() - digits);
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phi_digits() {
+        assert_eq!(phi(0), "1");
+        assert_eq!(phi(30), "1.618033988749894848204586834365");
+    }
+
+    #[test]
+    fn decimals_and_bounds() {
+        let big = |v: u32| BigUint::from(v);
+        assert_eq!(decimal(&big(1), &big(8), 5), "0.12500");
+        assert_eq!(decimal(&big(55), &big(34), 4), "1.6176");
+        assert_eq!(reciprocal_upper_bound(&big(3)), "3.34e-1");
+        assert_eq!(reciprocal_upper_bound(&big(1870)), "5.35e-4");
+        assert_eq!(reciprocal_upper_bound(&big(1000)), "1.00e-3");
+        assert_eq!(reciprocal_upper_bound(&big(1)), "1.00e0");
+    }
+}
@@ -0,0 +1,96 @@
+//! Pisano periods: π(m) is the period of F(n) mod m.
+
+use num_bigint::BigUint;
+
+use crate::{
+    deadline::{Deadline, Expired},
+    factor, fib,
+};
+
+/// π(m) together with the factorisation of m it was derived from, which
+/// costs more to find than the period itself.
+#[derive(Clone)]
+pub struct Period {
+    pub period: u128,
+    /// Ascending `(prime, exponent)` pairs.
+    pub factors: Vec<(u64, u32)>,
+}
+
+/// Returns π(m) for `m >= 1`, checking `deadline` between prime factors.
+///
+/// π is multiplicative over coprime factors (π(ab) = lcm(π(a), π(b))),
+/// and for a prime power π(p^k) = p^(k-1)·π(p). For a prime p the period
+/// divides p − 1 when p ≡ ±1 (mod 5) and 2(p + 1) when p ≡ ±2 (mod 5), so
+/// π(p) is found by dividing that bound down rather than by search.
+pub fn pisano(m: u64, deadline: &Deadline) -> Result<Period, Expired> {
+    let factors = factor::factorize(m);
+    let mut period = 1;
+    for &(p, k) in &factors {
+        deadline.check()?;
+        period = lcm(
+            period,
+            u128::from(p).pow(k - 1) * pisano_prime(p, deadline)?,
+        );
+    }
+    Ok(Period { period, factors })
+}
+
+fn pisano_prime(p: u64, deadline: &Deadline) -> Result<u128, Expired> {
+    match p {
+        2 => return Ok(3),
+        5 => return Ok(20),
+        _ => {}
+    }
+    // Every bound factor is at most p + 1, so it fits in a u64.
+    let (bound, mut factors) = if matches!(p % 5, 1 | 4) {
+        (u128::from(p - 1), factor::factorize(p - 1))
+    } else {
+        (2 * (u128::from(p) + 1), factor::factorize(p + 1))
+    };
+    factors.push((2, 1));
+    let mut period = bound;
+    for (q, _) in factors {
+        let q = u128::from(q);
+        while period.is_multiple_of(q) && is_period(period / q, p, deadline)? {
+            period /= q;
+        }
+    }
+    Ok(period)
+}
+
+/// Whether F(t) ≡ 0 and F(t+1) ≡ 1 (mod p), i.e. the sequence restarts at t.
+fn is_period(t: u128, p: u64, deadline: &Deadline) -> Result<bool, Expired> {
+    Ok(fib::fib_mod_pair(&BigUint::from(t), p, deadline)? == (0, 1 % p))
+}
+
+fn lcm(a: u128, b: u128) -> u128 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pisano_brute_force(m: u64) -> u128 {
+        let (mut a, mut b, mut t) = (0, 1 % m, 0);
+        loop {
+            (a, b) = (b, (a + b) % m);
+            t += 1;
+            if (a, b) == (0, 1 % m) {
+                return t;
+            }
+        }
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for m in 1..=2_000 {
+            let period = pisano(m, &Deadline::none()).unwrap().period;
+            assert_eq!(period, pisano_brute_force(m), "m = {m}");
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Bounded pool for CPU-heavy computations.
+//!
+//! Jobs run on tokio's blocking threads so they do not occupy the async
+//! workers that serve `/hello` and the probes. A semaphore caps how many run
+//! at once, and beyond that a fixed number may wait; anything more is
+//! rejected straight away rather than queued without bound.
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::sync::Semaphore;
+
+/// The pool and its queue are full.
+#[derive(Debug)]
+pub struct Saturated;
+
+/// Occupancy and rejections, for `/metrics`.
+pub struct Stats {
+    pub running: usize,
+    pub queued: usize,
+    pub rejected: u64,
+}
+
+pub struct Pool {
+    workers: Arc<Semaphore>,
+    /// Jobs running or waiting for a worker.
+    pending: Arc<AtomicUsize>,
+    running: Arc<AtomicUsize>,
+    rejected: AtomicU64,
+    capacity: usize,
+}
+
+impl Pool {
+    /// A pool running at most `workers` jobs with up to `queue` more
+    /// waiting.
+    pub fn new(workers: usize, queue: usize) -> Self {
+        Self {
+            workers: Arc::new(Semaphore::new(workers)),
+            pending: Arc::new(AtomicUsize::new(0)),
+            running: Arc::new(AtomicUsize::new(0)),
+            rejected: AtomicU64::new(0),
+            capacity: workers + queue,
+        }
+    }
+
+    /// Runs `job` on a blocking thread once a worker is free.
+    ///
+    /// If the caller is dropped while queued, the job never starts; once
+    /// started it runs to completion and keeps its worker and its place in
+    /// the pool until then.
+    pub async fn run<T, F>(&self, job: F) -> Result<T, Saturated>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let admitted = self
+            .pending
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |pending| {
+                (pending < self.capacity).then_some(pending + 1)
+            });
+        if admitted.is_err() {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(Saturated);
+        }
+        let pending = Decrement(Arc::clone(&self.pending));
+        let permit = Arc::clone(&self.workers)
+            .acquire_owned()
+            .await
+            .expect("worker semaphore is never closed");
+        self.running.fetch_add(1, Ordering::Relaxed);
+        let running = Decrement(Arc::clone(&self.running));
+        let task = tokio::task::spawn_blocking(move || {
+            let _guards = (permit, pending, running);
+            job()
+        });
+        match task.await {
+            Ok(value) => Ok(value),
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        let pending = self.pending.load(Ordering::Relaxed);
+        let running = self.running.load(Ordering::Relaxed);
+        Stats {
+            running,
+            queued: pending.saturating_sub(running),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Decrements a counter when dropped, however the job ends.
+struct Decrement(Arc<AtomicUsize>);
+
+impl Drop for Decrement {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_past_workers_plus_queue() {
+        let pool = Arc::new(Pool::new(1, 1));
+        let (release, wait) = mpsc::channel::<()>();
+
+        let blocked = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.run(move || wait.recv()).await })
+        };
+        let queued = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.run(|| 7).await })
+        };
+        while pool.stats().queued < 1 || pool.stats().running < 1 {
+            tokio::task::yield_now().await;
+        }
+        assert!(pool.run(|| 0).await.is_err());
+        assert_eq!(pool.stats().rejected, 1);
+
+        release.send(()).unwrap();
+        assert!(blocked.await.unwrap().is_ok());
+        assert_eq!(queued.await.unwrap().unwrap(), 7);
+        let stats = pool.stats();
+        assert_eq!((stats.running, stats.queued), (0, 0));
+    }
+}
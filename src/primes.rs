@@ -0,0 +1,232 @@
+//! Primality of big integers: exact below 2^64, Baillie–PSW above.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+use serde::Serialize;
+
+use crate::{
+    deadline::{Deadline, Expired},
+    factor, lucas,
+};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Primality {
+    /// Proven prime (the value fits in a `u64`, where Miller–Rabin with a
+    /// fixed base set is deterministic).
+    Prime,
+    /// Passed Baillie–PSW. No counterexample is known, but it is not a
+    /// proof.
+    ProbablePrime,
+    NotPrime,
+}
+
+/// Whether F(n) can be prime. F(m) divides F(n) whenever m divides n, so
+/// apart from F(4) = 3 a Fibonacci prime needs a prime index.
+pub fn fibonacci_index_may_be_prime(n: u64) -> bool {
+    n == 4 || factor::is_prime(n)
+}
+
+/// Primality of a signed value; zero, one and negatives are not prime.
+pub fn primality_of(n: &BigInt, deadline: &Deadline) -> Result<Primality, Expired> {
+    match n.sign() {
+        Sign::Plus => primality(n.magnitude(), deadline),
+        _ => Ok(Primality::NotPrime),
+    }
+}
+
+pub fn primality(n: &BigUint, deadline: &Deadline) -> Result<Primality, Expired> {
+    if let Some(small) = n.to_u64() {
+        return Ok(if factor::is_prime(small) {
+            Primality::Prime
+        } else {
+            Primality::NotPrime
+        });
+    }
+    Ok(if bpsw(n, deadline)? {
+        Primality::ProbablePrime
+    } else {
+        Primality::NotPrime
+    })
+}
+
+/// Baillie–PSW for an n above every trial divisor: a strong Fermat test
+/// to base 2 followed by a strong Lucas test with Selfridge's parameters.
+fn bpsw(n: &BigUint, deadline: &Deadline) -> Result<bool, Expired> {
+    const SMALL_PRIMES: [u32; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+    if SMALL_PRIMES.iter().any(|p| (n % p).is_zero()) {
+        return Ok(false);
+    }
+    Ok(strong_probable_prime(n, &BigUint::from(2u32), deadline)?
+        && strong_lucas_probable_prime(n, deadline)?)
+}
+
+/// Miller–Rabin round for an odd n > 2.
+fn strong_probable_prime(
+    n: &BigUint,
+    base: &BigUint,
+    deadline: &Deadline,
+) -> Result<bool, Expired> {
+    let n_minus_1 = n - 1u32;
+    let s = n_minus_1.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_1 >> s;
+    let mut x = pow_mod(base, &d, n, deadline)?;
+    if x.is_one() || x == n_minus_1 {
+        return Ok(true);
+    }
+    for _ in 1..s {
+        deadline.check()?;
+        x = &x * &x % n;
+        if x == n_minus_1 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// base^exponent mod n, a chunk of exponent bits at a time so that the
+/// deadline is checked between chunks. Each chunk still goes through
+/// `modpow`, which is much faster than squaring by hand.
+fn pow_mod(
+    base: &BigUint,
+    exponent: &BigUint,
+    n: &BigUint,
+    deadline: &Deadline,
+) -> Result<BigUint, Expired> {
+    const CHUNK_BITS: u64 = 64;
+    let shift = BigUint::one() << CHUNK_BITS;
+    let mut x = BigUint::one();
+    let mut low = exponent.bits().div_ceil(CHUNK_BITS) * CHUNK_BITS;
+    while low > 0 {
+        deadline.check()?;
+        low -= CHUNK_BITS;
+        let chunk = (exponent >> low) % &shift;
+        x = x.modpow(&shift, n) * base.modpow(&chunk, n) % n;
+    }
+    Ok(x)
+}
+
+/// Strong Lucas test for an odd n with no small factors.
+///
+/// D is the first of 5, −7, 9, −11, … with Jacobi (D/n) = −1; with P = 1
+/// and Q = (1 − D)/4, write n + 1 = d·2^s. n is a strong Lucas probable
+/// prime if U_d ≡ 0 or V_{d·2^r} ≡ 0 (mod n) for some 0 ≤ r < s.
+fn strong_lucas_probable_prime(n: &BigUint, deadline: &Deadline) -> Result<bool, Expired> {
+    // No D exists for perfect squares, so rule them out first.
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return Ok(false);
+    }
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(d, n) {
+            -1 => break,
+            // A shared factor with n; the small-prime screen guarantees
+            // |D| < n, so n is composite.
+            0 => return Ok(false),
+            _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+        }
+    }
+    let q = (1 - d) / 4;
+    let n_plus_1 = n + 1u32;
+    let s = n_plus_1.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_1 >> s;
+    let (u, mut v, mut qk) = lucas::lucas_uv_mod(1, q, &k, n, deadline)?;
+    if u.is_zero() || v.is_zero() {
+        return Ok(true);
+    }
+    for _ in 1..s {
+        deadline.check()?;
+        v = (&v * &v + (n - &qk) * 2u32) % n;
+        if v.is_zero() {
+            return Ok(true);
+        }
+        qk = &qk * &qk % n;
+    }
+    Ok(false)
+}
+
+/// Jacobi symbol (a/n) for odd n > 0.
+fn jacobi(a: i64, n: &BigUint) -> i32 {
+    let mut a = if a < 0 {
+        let r = BigUint::from(a.unsigned_abs()) % n;
+        if r.is_zero() {
+            r
+        } else {
+            n - r
+        }
+    } else {
+        BigUint::from(a.unsigned_abs()) % n
+    };
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        let n_mod_8 = (&n % 8u32).to_u32().unwrap_or(0);
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4u32) == BigUint::from(3u32) && (&n % 4u32) == BigUint::from(3u32) {
+            result = -result;
+        }
+        a %= &n;
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib;
+
+    #[test]
+    fn bpsw_matches_exact_test() {
+        let none = Deadline::none();
+        for n in (1_000_001u64..1_050_001).step_by(2) {
+            assert_eq!(
+                bpsw(&BigUint::from(n), &none).unwrap(),
+                factor::is_prime(n),
+                "n = {n}"
+            );
+        }
+        // Strong pseudoprimes to base 2 and Carmichael numbers.
+        for n in [
+            3_215_031_751u64,
+            2_152_302_898_747,
+            3_825_123_056_546_413_051,
+            1_024_651_291,
+        ] {
+            assert_eq!(
+                bpsw(&BigUint::from(n), &none).unwrap(),
+                factor::is_prime(n),
+                "n = {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn fibonacci_primes() {
+        // OEIS A001605: indices of Fibonacci primes.
+        let expected = [
+            3, 4, 5, 7, 11, 13, 17, 23, 29, 43, 47, 83, 131, 137, 359, 431, 433, 449,
+        ];
+        let of = |n: i64| primality(fib::fib(n).magnitude(), &Deadline::none()).unwrap();
+        let found: Vec<i64> = (0..=450)
+            .filter(|n| of(*n) != Primality::NotPrime)
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(of(131), Primality::ProbablePrime);
+        assert_eq!(of(47), Primality::Prime);
+        assert!((0..=450)
+            .filter(|n| of(*n) != Primality::NotPrime)
+            .all(|n| fibonacci_index_may_be_prime(n as u64)));
+        let past = Deadline::after(std::time::Duration::ZERO);
+        assert!(primality(fib::fib(449).magnitude(), &past).is_err());
+    }
+}
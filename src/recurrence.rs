@@ -0,0 +1,170 @@
+//! Terms of arbitrary constant-coefficient linear recurrences.
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::deadline::{Deadline, Expired};
+
+/// A recurrence a_n = c_1·a_{n−1} + … + c_k·a_{n−k} with its first k terms.
+pub struct Recurrence {
+    /// c_1, …, c_k.
+    pub coefficients: Vec<BigInt>,
+    /// a_0, …, a_{k−1}.
+    pub initial: Vec<BigInt>,
+    /// When set, every intermediate is reduced into `0..modulus`.
+    pub modulus: Option<BigInt>,
+}
+
+impl Recurrence {
+    fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    fn reduce(&self, value: BigInt) -> BigInt {
+        match &self.modulus {
+            Some(m) => ((value % m) + m) % m,
+            None => value,
+        }
+    }
+
+    /// Returns a_n using Kitamasa's method: x^n is reduced modulo the
+    /// characteristic polynomial x^k − c_1·x^{k−1} − … − c_k, and its
+    /// coefficients r_i give a_n = Σ r_i·a_i. That costs O(k² log n)
+    /// multiplications instead of the O(k³ log n) of matrix powers.
+    ///
+    /// The coefficient and initial-term vectors must be the same non-zero
+    /// length. Gives up between multiplications once `deadline` expires.
+    pub fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        let k = self.order();
+        if n < k as u64 {
+            return Ok(self.reduce(self.initial[n as usize].clone()));
+        }
+        let mut poly = vec![BigInt::zero(); k];
+        poly[0] = BigInt::one();
+        for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+            poly = self.square(&poly, deadline)?;
+            if (n >> bit) & 1 == 1 {
+                poly = self.times_x(poly, deadline)?;
+            }
+        }
+        let sum = poly
+            .iter()
+            .zip(&self.initial)
+            .map(|(r, a)| r * a)
+            .sum::<BigInt>();
+        Ok(self.reduce(sum))
+    }
+
+    /// Estimated size in bits of an unreduced a_n: n·log₂ Σ|c_i|, since
+    /// the terms grow no faster than (Σ|c_i|)^n.
+    pub fn bits(&self, n: u64) -> f64 {
+        let sum: BigInt = self.coefficients.iter().map(BigInt::abs).sum();
+        let base = sum.to_f64().unwrap_or(f64::INFINITY).max(1.0);
+        n as f64 * base.log2()
+    }
+
+    /// Squares a residue polynomial and reduces it back below degree k.
+    fn square(&self, poly: &[BigInt], deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        let k = self.order();
+        let mut product = vec![BigInt::zero(); 2 * k - 1];
+        for (i, a) in poly.iter().enumerate() {
+            deadline.check()?;
+            if a.is_zero() {
+                continue;
+            }
+            for (j, b) in poly.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+        // x^d = Σ c_j·x^{d−j}, folded from the top degree down.
+        for d in (k..2 * k - 1).rev() {
+            deadline.check()?;
+            let top = self.reduce(std::mem::take(&mut product[d]));
+            if top.is_zero() {
+                continue;
+            }
+            for (j, c) in self.coefficients.iter().enumerate() {
+                product[d - j - 1] += &top * c;
+            }
+        }
+        product.truncate(k);
+        Ok(product.into_iter().map(|c| self.reduce(c)).collect())
+    }
+
+    /// Multiplies a residue polynomial by x and reduces it.
+    fn times_x(&self, mut poly: Vec<BigInt>, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        deadline.check()?;
+        let top = poly.pop().unwrap_or_default();
+        poly.insert(0, BigInt::zero());
+        for (j, c) in self.coefficients.iter().enumerate() {
+            let d = self.order() - j - 1;
+            poly[d] = self.reduce(&poly[d] + &top * c);
+        }
+        Ok(poly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib;
+
+    fn recurrence(coefficients: &[i64], initial: &[i64], modulus: Option<i64>) -> Recurrence {
+        Recurrence {
+            coefficients: coefficients.iter().copied().map(BigInt::from).collect(),
+            initial: initial.iter().copied().map(BigInt::from).collect(),
+            modulus: modulus.map(BigInt::from),
+        }
+    }
+
+    #[test]
+    fn matches_direct_iteration() {
+        let cases: [(&[i64], &[i64]); 4] = [
+            (&[1, 1], &[0, 1]),
+            (&[2], &[3]),
+            (&[0, 1, -3], &[1, -2, 5]),
+            (&[1, 1, 1, 1, 1], &[4, 0, 0, 7, 1]),
+        ];
+        for (coefficients, initial) in cases {
+            let k = coefficients.len();
+            let mut terms: Vec<BigInt> = initial.iter().copied().map(BigInt::from).collect();
+            while terms.len() < 60 {
+                let next = (1..=k)
+                    .map(|j| &terms[terms.len() - j] * coefficients[j - 1])
+                    .sum();
+                terms.push(next);
+            }
+            let plain = recurrence(coefficients, initial, None);
+            let modular = recurrence(coefficients, initial, Some(97));
+            for (n, expected) in terms.iter().enumerate() {
+                let none = Deadline::none();
+                assert_eq!(
+                    &plain.term(n as u64, &none).unwrap(),
+                    expected,
+                    "{coefficients:?} n={n}"
+                );
+                assert!(expected.bits() as f64 <= plain.bits(n as u64) + 64.0);
+                let reduced = ((expected % 97) + 97) % 97;
+                assert_eq!(
+                    modular.term(n as u64, &none).unwrap(),
+                    reduced,
+                    "{coefficients:?} n={n} mod 97"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fibonacci_recurrence() {
+        assert_eq!(
+            recurrence(&[1, 1], &[0, 1], None)
+                .term(1_000, &Deadline::none())
+                .unwrap(),
+            fib::fib(1_000)
+        );
+        let past = Deadline::after(std::time::Duration::ZERO);
+        assert!(recurrence(&[1, 1], &[0, 1], None)
+            .term(1_000, &past)
+            .is_err());
+    }
+}
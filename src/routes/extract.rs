@@ -0,0 +1,137 @@
+//! Drop-in replacements for axum's `Path`, `Query` and `Json` extractors
+//! whose rejections use the JSON error envelope instead of plain text.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        path::ErrorKind,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        FromRequest, FromRequestParts, MatchedPath, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{error::ApiError, state::AppState};
+
+pub struct Path<T>(pub T);
+
+pub struct Query<T>(pub T);
+
+/// Also usable as a response body, so handlers need only one `Json`.
+pub struct Json<T>(pub T);
+
+impl<T> FromRequestParts<Arc<AppState>> for Path<T>
+where
+    T: DeserializeOwned + Send,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(rejection) => Err(path_error(parts, rejection, state.config.max_n)),
+        }
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Self(value))
+            .map_err(|rejection: QueryRejection| {
+                ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_query",
+                    rejection.body_text(),
+                )
+            })
+    }
+}
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(request, state)
+            .await
+            .map(|axum::Json(value)| Self(value))
+            .map_err(|rejection: JsonRejection| {
+                ApiError::new(rejection.status(), "invalid_body", rejection.body_text())
+            })
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Names the failing parameter and, for `n`, reuses the codes the handlers
+/// return for indices they reject themselves. An integer literal that failed
+/// to parse into an integer type is out of range rather than malformed.
+fn path_error(parts: &Parts, rejection: PathRejection, max_n: u64) -> ApiError {
+    let PathRejection::FailedToDeserializePathParams(error) = &rejection else {
+        return ApiError::new(rejection.status(), "invalid_path", rejection.body_text());
+    };
+    let names = || {
+        parts
+            .extensions
+            .get::<MatchedPath>()
+            .map(|path| {
+                path.as_str()
+                    .split('/')
+                    .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let (name, value, expected_type) = match error.kind() {
+        ErrorKind::ParseErrorAtKey {
+            key,
+            value,
+            expected_type,
+        } => (Some(key.clone()), value, *expected_type),
+        ErrorKind::ParseErrorAtIndex {
+            index,
+            value,
+            expected_type,
+        } => (names().get(*index).cloned(), value, *expected_type),
+        ErrorKind::ParseError {
+            value,
+            expected_type,
+        } => (names().first().cloned(), value, *expected_type),
+        _ => return ApiError::new(rejection.status(), "invalid_path", error.body_text()),
+    };
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let integer = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+    let out_of_range =
+        integer && (expected_type.starts_with('i') || expected_type.starts_with('u'));
+    match name.as_deref() {
+        Some("n") if out_of_range => ApiError::n_out_of_range(value, max_n),
+        Some("n") => ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_n",
+            format!("n = {value:?} is not an integer"),
+        ),
+        _ => ApiError::new(StatusCode::BAD_REQUEST, "invalid_path", error.body_text()),
+    }
+}
@@ -0,0 +1,177 @@
+use axum::{
+    body::{self, Body},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use serde::Deserialize;
+
+use super::extract::Query;
+use crate::{
+    error::ApiError,
+    fibcode::{Decoder, Encoder},
+};
+
+/// JSON bodies are parsed whole; raw bodies are streamed without a limit.
+const JSON_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// `POST /fibcode/encode`
+///
+/// A JSON body is an array of positive integers. Any other body is treated
+/// as raw bytes, each byte b encoded as the integer b + 1, and is encoded
+/// chunk by chunk as it arrives. The response is the packed code.
+pub async fn encode(headers: HeaderMap, body: Body) -> Result<Response, ApiError> {
+    if !is_json(&headers) {
+        let chunks = body
+            .into_data_stream()
+            .map(Some)
+            .chain(stream::iter([None]));
+        let encoded = chunks.scan(Encoder::default(), |encoder, chunk| {
+            let mut out = Vec::new();
+            let item = match chunk {
+                Some(Ok(bytes)) => {
+                    for byte in bytes {
+                        encoder.push(u64::from(byte) + 1, &mut out);
+                    }
+                    Ok(Bytes::from(out))
+                }
+                Some(Err(err)) => Err(err),
+                None => {
+                    std::mem::take(encoder).finish(&mut out);
+                    Ok(Bytes::from(out))
+                }
+            };
+            std::future::ready(Some(item))
+        });
+        return Ok(binary(Body::from_stream(encoded)));
+    }
+
+    let raw = body::to_bytes(body, JSON_BODY_LIMIT).await.map_err(|err| {
+        ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "body_too_large",
+            err.to_string(),
+        )
+    })?;
+    let values: Vec<u64> = serde_json::from_slice(&raw)
+        .map_err(|err| ApiError::new(StatusCode::BAD_REQUEST, "invalid_body", err.to_string()))?;
+    if let Some(position) = values.iter().position(|v| *v == 0) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_value",
+            format!(
+                "value at position {position} is zero; Fibonacci coding needs positive integers"
+            ),
+        ));
+    }
+    let mut encoder = Encoder::default();
+    let mut out = Vec::new();
+    for value in values {
+        encoder.push(value, &mut out);
+    }
+    encoder.finish(&mut out);
+    Ok(binary(Body::from(out)))
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// A JSON array of the decoded integers.
+    #[default]
+    Json,
+    /// Raw bytes, each decoded integer v written as the byte v − 1.
+    Bytes,
+}
+
+#[derive(Deserialize)]
+pub struct DecodeQuery {
+    #[serde(default)]
+    output: Output,
+}
+
+/// `POST /fibcode/decode`: the body is packed code, decoded and written
+/// back as it streams in.
+///
+/// Both outputs are streamed, so malformed input discovered part-way
+/// through aborts the response rather than producing an error status.
+pub async fn decode(Query(query): Query<DecodeQuery>, body: Body) -> Response {
+    let output = query.output;
+    let chunks = body
+        .into_data_stream()
+        .map(Some)
+        .chain(stream::iter([None]));
+    let decoded = chunks.scan(
+        (Some(Decoder::default()), true),
+        move |(decoder, first), chunk| {
+            let Some(active) = decoder.as_mut() else {
+                return std::future::ready(None);
+            };
+            let mut values = Vec::new();
+            let result = match chunk {
+                Some(Ok(bytes)) => active.feed(&bytes, &mut values).map_err(axum::Error::new),
+                Some(Err(err)) => Err(err),
+                None => decoder
+                    .take()
+                    .map_or(Ok(()), |d| d.finish().map_err(axum::Error::new)),
+            };
+            let item = result.and_then(|()| render(output, &values, first, decoder.is_none()));
+            if item.is_err() {
+                *decoder = None;
+            }
+            std::future::ready(Some(item))
+        },
+    );
+    let content_type = match output {
+        Output::Json => "application/json",
+        Output::Bytes => "application/octet-stream",
+    };
+    (
+        [(header::CONTENT_TYPE, content_type)],
+        Body::from_stream(decoded),
+    )
+        .into_response()
+}
+
+/// Serialises one chunk of decoded values. JSON output is an array spread
+/// over the whole stream: `[` opens it on the first chunk and `]` closes it
+/// on the last.
+fn render(
+    output: Output,
+    values: &[u64],
+    first: &mut bool,
+    last: bool,
+) -> Result<Bytes, axum::Error> {
+    match output {
+        Output::Bytes => values
+            .iter()
+            .map(|v| {
+                u8::try_from(v - 1)
+                    .map_err(|_| axum::Error::new(format!("value {v} does not fit in a byte")))
+            })
+            .collect::<Result<Vec<u8>, _>>()
+            .map(Bytes::from),
+        Output::Json => {
+            let mut text = String::new();
+            for value in values {
+                text.push(if std::mem::take(first) { '[' } else { ',' });
+                text.push_str(&value.to_string());
+            }
+            if last {
+                text.push_str(if *first { "[]" } else { "]" });
+            }
+            Ok(Bytes::from(text))
+        }
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+fn binary(body: Body) -> Response {
+    ([(header::CONTENT_TYPE, "application/octet-stream")], body).into_response()
+}
@@ -0,0 +1,937 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use num_bigint::{BigInt, BigUint};
+use num_traits::One;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::extract::{Json, Path, Query};
+use crate::{
+    approx,
+    cache::{self, Key},
+    config::Config,
+    deadline::Deadline,
+    error::{ApiError, ErrorBody},
+    fib,
+    primes::{self, Primality},
+    state::AppState,
+};
+
+#[derive(Serialize, Clone)]
+pub struct FibResponse {
+    n: i64,
+    /// Decimal representation; F(n) outgrows every fixed-width JSON number.
+    result: String,
+    /// Number of decimal digits, not counting a leading minus sign.
+    digits: usize,
+    /// False when `result` is not F(n) itself, e.g. a saturated value.
+    exact: bool,
+    /// Primality of F(n), present when requested with `prime=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prime: Option<Primality>,
+}
+
+impl FibResponse {
+    /// Wraps an exact F(n).
+    pub fn exact(n: i64, value: &BigInt) -> Self {
+        Self::new(n, value, true)
+    }
+
+    fn new(n: i64, value: &BigInt, exact: bool) -> Self {
+        let result = value.to_string();
+        Self {
+            n,
+            digits: result.trim_start_matches('-').len(),
+            result,
+            exact,
+            prime: None,
+        }
+    }
+}
+
+/// Rejects indices whose magnitude exceeds `FIB_MAX_N`.
+pub fn check_n(config: &Config, n: i64) -> Result<(), ApiError> {
+    if n.unsigned_abs() > config.max_n {
+        return Err(ApiError::n_out_of_range(n, config.max_n));
+    }
+    Ok(())
+}
+
+/// What to do when |F(n)| does not fit in the requested `bits`.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowMode {
+    /// Reject the request with 422 and an `overflow` error code.
+    #[default]
+    Error,
+    /// Clamp to the largest representable value and report `exact: false`.
+    Saturate,
+}
+
+/// How `GET /fibonacci/{n}` computes its answer.
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FibMode {
+    /// The full value, for |n| up to `FIB_MAX_N`.
+    #[default]
+    Exact,
+    /// Leading and trailing digits only, for any `i64` index.
+    Approx,
+}
+
+#[derive(Deserialize)]
+pub struct FibQuery {
+    #[serde(default)]
+    mode: FibMode,
+    /// Leading significant digits to return in approx mode.
+    leading: Option<u32>,
+    /// Trailing digits to return in approx mode.
+    last: Option<u32>,
+    /// Magnitude bits of the integer type the client will store the
+    /// result in.
+    bits: Option<u32>,
+    #[serde(default)]
+    overflow: OverflowMode,
+    /// Also report whether F(n) is prime.
+    #[serde(default)]
+    prime: bool,
+}
+
+pub async fn fibonacci(
+    State(state): State<Arc<AppState>>,
+    Path(n): Path<i64>,
+    Query(query): Query<FibQuery>,
+    deadline: Deadline,
+) -> Result<Response, ApiError> {
+    if query.mode == FibMode::Approx {
+        return approximate(&state, n, &query, deadline)
+            .await
+            .map(|body| Json(body).into_response());
+    }
+    check_n(&state.config, n)?;
+    let (cached, cache_status) = state
+        .term(
+            Key::new("fibonacci", n, None),
+            deadline.clone(),
+            move |deadline| fib::fib_within(n, deadline),
+        )
+        .await?;
+    let mut value = BigInt::clone(&cached);
+    let mut exact = true;
+    if let Some(bits) = query.bits {
+        if value.bits() > u64::from(bits) {
+            match query.overflow {
+                OverflowMode::Error => {
+                    return Err(ApiError::new(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "overflow",
+                        format!("F({n}) does not fit in {bits} bits"),
+                    ))
+                }
+                OverflowMode::Saturate => {
+                    let max = BigInt::from((BigUint::one() << bits) - 1u32);
+                    value = if value.sign() == num_bigint::Sign::Minus {
+                        -max
+                    } else {
+                        max
+                    };
+                    exact = false;
+                }
+            }
+        }
+    }
+    // After the overflow check, which is cheap and may reject the request.
+    let prime = if !query.prime {
+        None
+    } else if !primes::fibonacci_index_may_be_prime(n.unsigned_abs()) {
+        Some(Primality::NotPrime)
+    } else {
+        let term = Arc::clone(&cached);
+        Some(
+            state
+                .run(deadline, move |deadline| {
+                    primes::primality_of(&term, deadline)
+                })
+                .await?,
+        )
+    };
+    Ok((
+        cache_status,
+        Json(FibResponse {
+            prime,
+            ..FibResponse::new(n, &value, exact)
+        }),
+    )
+        .into_response())
+}
+
+const DEFAULT_LEADING_DIGITS: u32 = 20;
+const MAX_LEADING_DIGITS: u32 = 100;
+const DEFAULT_LAST_DIGITS: u32 = 10;
+/// 10^19 is the largest power of ten that fits in a `u64` modulus.
+const MAX_LAST_DIGITS: u32 = 19;
+/// Below this index the ψ^n term of Binet's formula can still flip a
+/// truncated digit, so the value is computed exactly instead.
+const APPROX_MIN_N: u64 = 1_000;
+
+/// `?mode=approx`: F(n) ≈ leading_digits × 10^(exponent − len + 1), with
+/// the last digits exact.
+#[derive(Serialize)]
+pub struct ApproxResponse {
+    n: i64,
+    /// The first significant digits of F(n), with a minus sign if F(n) is
+    /// negative.
+    leading_digits: String,
+    /// Decimal exponent of the leading digit.
+    exponent: u64,
+    /// Number of decimal digits of F(n), not counting a minus sign.
+    digits: u64,
+    /// |F(n)| mod 10^last, zero-padded unless F(n) is shorter.
+    last_digits: String,
+    exact: bool,
+}
+
+async fn approximate(
+    state: &AppState,
+    n: i64,
+    query: &FibQuery,
+    deadline: Deadline,
+) -> Result<ApproxResponse, ApiError> {
+    let leading = query.leading.unwrap_or(DEFAULT_LEADING_DIGITS);
+    if !(1..=MAX_LEADING_DIGITS).contains(&leading) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_leading",
+            format!("leading must be between 1 and {MAX_LEADING_DIGITS}"),
+        ));
+    }
+    let last = query.last.unwrap_or(DEFAULT_LAST_DIGITS);
+    if last > MAX_LAST_DIGITS {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_last",
+            format!("last must be at most {MAX_LAST_DIGITS}"),
+        ));
+    }
+    let magnitude = n.unsigned_abs();
+    let approximation = if magnitude <= state.config.max_n.max(APPROX_MIN_N) {
+        state
+            .run(deadline, move |deadline| {
+                let (value, _) = fib::fib_pair_within(magnitude, deadline)?;
+                Ok(approx::from_exact(&value, leading, last))
+            })
+            .await?
+    } else {
+        approx::approximate(magnitude, leading, last)
+    };
+    // F(-n) = (-1)^(n+1) F(n).
+    let sign = if n < 0 && n % 2 == 0 { "-" } else { "" };
+    Ok(ApproxResponse {
+        n,
+        leading_digits: format!("{sign}{}", approximation.leading),
+        exponent: approximation.exponent,
+        digits: approximation.exponent + 1,
+        last_digits: approximation.last,
+        exact: false,
+    })
+}
+
+/// F(n) mod m. `n` is echoed as a string because it may exceed any JSON
+/// number.
+#[derive(Serialize)]
+pub struct FibModResponse {
+    n: String,
+    modulus: u64,
+    result: String,
+    digits: usize,
+    exact: bool,
+}
+
+/// `GET /fibonacci/{n}/mod/{m}`: `n` is a decimal index of up to
+/// `FIB_MAX_INDEX_DIGITS` digits.
+pub async fn modulo(
+    State(state): State<Arc<AppState>>,
+    Path((n, m)): Path<(String, String)>,
+    deadline: Deadline,
+) -> Result<(cache::Status, Json<FibModResponse>), ApiError> {
+    if n.len() > state.config.max_index_digits {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "n_too_large",
+            format!(
+                "index has {} digits, more than the configured maximum of {}",
+                n.len(),
+                state.config.max_index_digits
+            ),
+        ));
+    }
+    // `BigUint::from_str` also accepts `_` separators and a `+` sign.
+    let index = Some(n.as_str())
+        .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|n| n.parse::<BigUint>().ok())
+        .ok_or_else(|| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_n",
+                "index must be a non-negative decimal integer",
+            )
+        })?;
+    let modulus = m.parse::<u64>().ok().filter(|m| *m > 0).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_modulus",
+            format!("modulus must be between 1 and {}", u64::MAX),
+        )
+    })?;
+    let n = index.to_string();
+    let key = Key::new("fibonacci", index.clone(), Some(modulus));
+    let (result, cache_status) = state
+        .term(key, deadline, move |deadline| {
+            Ok(fib::fib_mod(&index, modulus, deadline)?.into())
+        })
+        .await?;
+    let result = result.to_string();
+    Ok((
+        cache_status,
+        Json(FibModResponse {
+            n,
+            modulus,
+            digits: result.len(),
+            result,
+            exact: true,
+        }),
+    ))
+}
+
+#[derive(Serialize)]
+pub struct InverseResponse {
+    value: String,
+    is_fibonacci: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<i64>,
+}
+
+/// Parses a decimal integer path segment, bounded by `FIB_MAX_VALUE_DIGITS`.
+pub fn parse_value(config: &Config, raw: &str) -> Result<BigInt, ApiError> {
+    let digits = raw.trim_start_matches(['-', '+']).len();
+    if digits > config.max_value_digits {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "value_too_large",
+            format!(
+                "value has {digits} digits, more than the configured maximum of {}",
+                config.max_value_digits
+            ),
+        ));
+    }
+    raw.parse().map_err(|_| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_value",
+            "value must be a decimal integer",
+        )
+    })
+}
+
+/// `GET /fibonacci/inverse/{value}`: whether `value` is a Fibonacci number
+/// and, if so, its index.
+pub async fn inverse(
+    State(state): State<Arc<AppState>>,
+    Path(raw): Path<String>,
+) -> Result<Json<InverseResponse>, ApiError> {
+    let value = parse_value(&state.config, &raw)?;
+    let index = fib::index_of(&value);
+    Ok(Json(InverseResponse {
+        value: value.to_string(),
+        is_fibonacci: index.is_some(),
+        index,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct PrimesQuery {
+    /// Largest index to test, capped at `FIB_PRIMES_MAX_LIMIT`.
+    limit: u64,
+}
+
+#[derive(Serialize)]
+pub struct FibPrime {
+    n: u64,
+    result: String,
+    prime: Primality,
+}
+
+/// `GET /fibonacci/primes?limit=`: every n ≤ limit with F(n) prime or
+/// probably prime.
+///
+/// Only indices that [`primes::fibonacci_index_may_be_prime`] allows are
+/// tested.
+pub async fn primes(
+    State(state): State<Arc<AppState>>,
+    Query(PrimesQuery { limit }): Query<PrimesQuery>,
+    deadline: Deadline,
+) -> Result<Json<Vec<FibPrime>>, ApiError> {
+    let max_limit = state.config.primes_max_limit.min(state.config.max_n);
+    if limit > max_limit {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "limit_too_large",
+            format!("limit = {limit} exceeds the configured maximum of {max_limit}"),
+        ));
+    }
+    let found = state
+        .run(deadline, move |deadline| {
+            let mut found = Vec::new();
+            for n in (0..=limit).filter(|n| primes::fibonacci_index_may_be_prime(*n)) {
+                let (value, _) = fib::fib_pair_within(n, deadline)?;
+                match primes::primality(&value, deadline)? {
+                    Primality::NotPrime => {}
+                    prime => found.push(FibPrime {
+                        n,
+                        result: value.to_string(),
+                        prime,
+                    }),
+                }
+            }
+            Ok(found)
+        })
+        .await?;
+    Ok(Json(found))
+}
+
+#[derive(Deserialize)]
+pub struct SumQuery {
+    from: i64,
+    /// Inclusive upper bound.
+    to: i64,
+}
+
+#[derive(Serialize)]
+pub struct SumResponse {
+    from: i64,
+    to: i64,
+    /// Σ F(i) over the range, as a decimal string.
+    sum: String,
+    /// Σ F(i)² over the range, as a decimal string.
+    sum_of_squares: String,
+    exact: bool,
+}
+
+/// `GET /fibonacci/sum?from=&to=`: closed-form, so no span limit applies.
+pub async fn sum(
+    State(state): State<Arc<AppState>>,
+    Query(SumQuery { from, to }): Query<SumQuery>,
+    deadline: Deadline,
+) -> Result<Json<SumResponse>, ApiError> {
+    if from > to {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_range",
+            format!("from = {from} is greater than to = {to}"),
+        ));
+    }
+    check_n(&state.config, from)?;
+    check_n(&state.config, to)?;
+    let (sum, sum_of_squares) = state
+        .run(deadline, move |deadline| {
+            let sum = fib::sum(from, to, deadline)?.to_string();
+            Ok((sum, fib::sum_of_squares(from, to, deadline)?.to_string()))
+        })
+        .await?;
+    Ok(Json(SumResponse {
+        from,
+        to,
+        sum,
+        sum_of_squares,
+        exact: true,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct GcdQuery {
+    a: i64,
+    b: i64,
+}
+
+#[derive(Serialize)]
+pub struct GcdResponse {
+    a: i64,
+    b: i64,
+    /// gcd(|a|, |b|), the index of the result.
+    index: u64,
+    /// gcd(F(a), F(b)) = F(gcd(a, b)), as a decimal string.
+    result: String,
+    exact: bool,
+}
+
+/// `GET /fibonacci/gcd?a=&b=`
+pub async fn gcd(
+    State(state): State<Arc<AppState>>,
+    Query(GcdQuery { a, b }): Query<GcdQuery>,
+    deadline: Deadline,
+) -> Result<Json<GcdResponse>, ApiError> {
+    check_n(&state.config, a)?;
+    check_n(&state.config, b)?;
+    let (index, result) = state
+        .run(deadline, move |deadline| {
+            let (index, value) = fib::gcd(a, b, deadline)?;
+            Ok((index, value.to_string()))
+        })
+        .await?;
+    Ok(Json(GcdResponse {
+        a,
+        b,
+        index,
+        result,
+        exact: true,
+    }))
+}
+
+/// One entry of a batch reply: either the term or why that index failed.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum BatchItem {
+    Ok(FibResponse),
+    Err { n: Value, error: ErrorBody },
+}
+
+/// `POST /fibonacci/batch`: a JSON array of indices in, one item per index
+/// out, in request order. Repeated indices are computed once.
+pub async fn batch(
+    State(state): State<Arc<AppState>>,
+    deadline: Deadline,
+    Json(indices): Json<Vec<Value>>,
+) -> Result<Json<Vec<BatchItem>>, ApiError> {
+    let config = &state.config;
+    if indices.len() > config.max_batch {
+        return Err(ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "batch_too_large",
+            format!(
+                "batch of {} indices exceeds the configured maximum of {}",
+                indices.len(),
+                config.max_batch
+            ),
+        ));
+    }
+    let mut computed: HashMap<i64, FibResponse> = HashMap::new();
+    let mut items = Vec::with_capacity(indices.len());
+    for raw in indices {
+        let n = match batch_index(config, &raw) {
+            Ok(n) => n,
+            Err(error) => {
+                items.push(BatchItem::Err {
+                    n: raw,
+                    error: error.body,
+                });
+                continue;
+            }
+        };
+        // A full pool or a spent deadline only fails the items it hits.
+        let response = match computed.entry(n) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match state
+                .term(
+                    Key::new("fibonacci", n, None),
+                    deadline.clone(),
+                    move |deadline| fib::fib_within(n, deadline),
+                )
+                .await
+            {
+                Ok((value, _)) => entry.insert(FibResponse::exact(n, &value)),
+                Err(error) => {
+                    items.push(BatchItem::Err {
+                        n: raw,
+                        error: error.body,
+                    });
+                    continue;
+                }
+            },
+        };
+        items.push(BatchItem::Ok(response.clone()));
+    }
+    Ok(Json(items))
+}
+
+/// Validates one batch entry. JSON integers beyond `i64` arrive as `u64`
+/// or, past that, as whole floats; those are out of range, not malformed.
+fn batch_index(config: &Config, raw: &Value) -> Result<i64, ApiError> {
+    if let Some(n) = raw.as_i64() {
+        check_n(config, n)?;
+        return Ok(n);
+    }
+    let whole = raw.is_u64()
+        || raw
+            .as_f64()
+            .is_some_and(|x| x.fract() == 0.0 && x.abs() >= 2f64.powi(63));
+    if whole {
+        return Err(ApiError::n_out_of_range(raw, config.max_n));
+    }
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "invalid_n",
+        "index must be an integer",
+    ))
+}
+
+#[derive(Serialize)]
+pub struct Term {
+    n: i64,
+    result: String,
+}
+
+impl Term {
+    pub fn new(n: i64, value: &BigInt) -> Self {
+        Self {
+            n,
+            result: value.to_string(),
+        }
+    }
+}
+
+/// One page of consecutive terms. `next_cursor` is present while more
+/// terms remain in the requested range.
+#[derive(Serialize)]
+pub struct Page {
+    items: Vec<Term>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    /// Opaque token taken from a previous page's `next_cursor`.
+    cursor: Option<String>,
+    /// Page size, capped at the configured `FIB_PAGE_SIZE`.
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct RangeQuery {
+    from: i64,
+    /// Inclusive upper bound.
+    to: i64,
+    // Not `#[serde(flatten)] PageQuery`: flattening loses the numeric
+    // parsing of query-string values.
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+/// `GET /fibonacci?from=a&to=b`
+pub async fn range(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RangeQuery>,
+    deadline: Deadline,
+) -> Result<Json<Page>, ApiError> {
+    let page_query = PageQuery {
+        cursor: query.cursor,
+        limit: query.limit,
+    };
+    page(&state, query.from, query.to, &page_query, deadline)
+        .await
+        .map(Json)
+}
+
+/// `GET /fibonacci/sequence/{count}`: the first `count` terms, from F(0).
+pub async fn sequence(
+    State(state): State<Arc<AppState>>,
+    Path(count): Path<u64>,
+    Query(query): Query<PageQuery>,
+    deadline: Deadline,
+) -> Result<Json<Page>, ApiError> {
+    match count.checked_sub(1) {
+        Some(to) => {
+            let to =
+                i64::try_from(to).map_err(|_| ApiError::n_out_of_range(to, state.config.max_n))?;
+            page(&state, 0, to, &query, deadline).await.map(Json)
+        }
+        None => Ok(Json(Page {
+            items: Vec::new(),
+            next_cursor: None,
+        })),
+    }
+}
+
+async fn page(
+    state: &AppState,
+    from: i64,
+    to: i64,
+    query: &PageQuery,
+    deadline: Deadline,
+) -> Result<Page, ApiError> {
+    let config = &state.config;
+    check_range(config, from, to)?;
+    let start = match &query.cursor {
+        None => from,
+        Some(cursor) => cursor
+            .parse()
+            .ok()
+            .filter(|n| (from..=to).contains(n))
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_cursor",
+                    format!("cursor {cursor:?} does not belong to this range"),
+                )
+            })?,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(config.page_size)
+        .clamp(1, config.page_size);
+    let end = to.min(start.saturating_add_unsigned(limit - 1));
+    let items = state
+        .run(deadline, move |deadline| {
+            let mut items = Vec::new();
+            for (n, value) in fib::terms_within(start, deadline)?.take_while(|(n, _)| *n <= end) {
+                deadline.check()?;
+                items.push(Term::new(n, &value));
+            }
+            Ok(items)
+        })
+        .await?;
+    Ok(Page {
+        items,
+        next_cursor: (end < to).then(|| (end + 1).to_string()),
+    })
+}
+
+/// Validates an inclusive range against `FIB_MAX_N` and `FIB_MAX_SPAN`.
+pub fn check_range(config: &Config, from: i64, to: i64) -> Result<(), ApiError> {
+    if from > to {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_range",
+            format!("from = {from} is greater than to = {to}"),
+        ));
+    }
+    check_n(config, from)?;
+    check_n(config, to)?;
+    let span = from.abs_diff(to);
+    if span >= config.max_span {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "span_too_large",
+            format!(
+                "range of {} terms exceeds the configured maximum of {}",
+                u128::from(span) + 1,
+                config.max_span
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use serde_json::{json, Value};
+
+    use super::super::{
+        router,
+        tests::{app, config, get, post},
+    };
+    use crate::{config::Config, state::AppState};
+
+    #[tokio::test]
+    async fn overflow_is_rejected_by_default() {
+        let app = app(config());
+        let (status, body) = get(&app, "/fibonacci/93?bits=64").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"], "12200160415121876738");
+        assert_eq!(body["exact"], true);
+
+        let (status, body) = get(&app, "/fibonacci/94?bits=64").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "overflow");
+    }
+
+    #[tokio::test]
+    async fn saturation_clamps_to_the_signed_bound() {
+        let app = app(config());
+        let (status, body) = get(&app, "/fibonacci/94?bits=64&overflow=saturate").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"], "18446744073709551615");
+        assert_eq!(body["exact"], false);
+
+        // F(-94) = -F(94), since the index is even.
+        let (status, body) = get(&app, "/fibonacci/-94?bits=64&overflow=saturate").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"], "-18446744073709551615");
+        assert_eq!(body["exact"], false);
+
+        let (_, body) = get(&app, "/fibonacci/-93?bits=64&overflow=saturate").await;
+        assert_eq!(
+            body,
+            json!({"n": -93, "result": "12200160415121876738", "digits": 20, "exact": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_answers_in_request_order_and_computes_repeats_once() {
+        let state = Arc::new(AppState::new(config()));
+        let app = router(Arc::clone(&state));
+        let (status, body) = post(&app, "/fibonacci/batch", json!([10, -3, 10, 1])).await;
+        assert_eq!(status, StatusCode::OK);
+        let results: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| (item["n"].clone(), item["result"].clone()))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (json!(10), json!("55")),
+                (json!(-3), json!("2")),
+                (json!(10), json!("55")),
+                (json!(1), json!("1")),
+            ]
+        );
+        let stats = state.cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 3));
+    }
+
+    #[tokio::test]
+    async fn batch_reports_bad_indices_per_item() {
+        let app = app(config());
+        let (status, body) = post(
+            &app,
+            "/fibonacci/batch",
+            json!([5, "x", 1.5, 1_000_000, 18_446_744_073_709_551_615u64, 1e30]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let codes: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["error"]["code"].clone())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                Value::Null,
+                json!("invalid_n"),
+                json!("invalid_n"),
+                json!("n_out_of_range"),
+                json!("n_out_of_range"),
+                json!("n_out_of_range"),
+            ]
+        );
+        assert_eq!(body[0]["result"], "5");
+        assert_eq!(body[1]["n"], "x");
+    }
+
+    #[tokio::test]
+    async fn oversized_batch_is_rejected() {
+        let app = app(Config {
+            max_batch: 3,
+            ..config()
+        });
+        let (status, _) = post(&app, "/fibonacci/batch", json!([1, 2, 3])).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = post(&app, "/fibonacci/batch", json!([1, 2, 3, 4])).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"]["code"], "batch_too_large");
+    }
+
+    #[tokio::test]
+    async fn range_pages_follow_the_cursor() {
+        let app = app(Config {
+            page_size: 3,
+            ..config()
+        });
+        let mut uri = "/fibonacci?from=-2&to=4".to_string();
+        let mut pages = Vec::new();
+        loop {
+            let (status, body) = get(&app, &uri).await;
+            assert_eq!(status, StatusCode::OK);
+            pages.push(body["items"].as_array().unwrap().len());
+            for item in body["items"].as_array().unwrap() {
+                let n = item["n"].as_i64().unwrap();
+                assert_eq!(item["result"], crate::fib::fib(n).to_string());
+            }
+            match body["next_cursor"].as_str() {
+                Some(cursor) => uri = format!("/fibonacci?from=-2&to=4&cursor={cursor}"),
+                None => break,
+            }
+        }
+        assert_eq!(pages, [3, 3, 1]);
+
+        let (_, body) = get(&app, "/fibonacci/sequence/5?cursor=3").await;
+        assert_eq!(
+            body,
+            json!({"items": [{"n": 3, "result": "2"}, {"n": 4, "result": "3"}]})
+        );
+    }
+
+    #[tokio::test]
+    async fn cursor_outside_the_range_is_rejected() {
+        let app = app(config());
+        for cursor in ["5", "-3", "x"] {
+            let uri = format!("/fibonacci?from=-2&to=4&cursor={cursor}");
+            let (status, body) = get(&app, &uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{cursor}");
+            assert_eq!(body["error"]["code"], "invalid_cursor");
+        }
+    }
+
+    #[tokio::test]
+    async fn limit_is_clamped_to_the_page_size() {
+        let app = app(Config {
+            page_size: 3,
+            ..config()
+        });
+        for (limit, len, next) in [(2, 2, "2"), (100, 3, "3"), (0, 1, "1")] {
+            let (_, body) = get(&app, &format!("/fibonacci?from=0&to=9&limit={limit}")).await;
+            assert_eq!(body["items"].as_array().unwrap().len(), len, "{limit}");
+            assert_eq!(body["next_cursor"], next);
+        }
+    }
+
+    #[tokio::test]
+    async fn span_is_capped() {
+        let app = app(Config {
+            max_span: 10,
+            ..config()
+        });
+        let (status, _) = get(&app, "/fibonacci?from=-5&to=4").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = get(&app, "/fibonacci?from=-5&to=5").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "span_too_large");
+        let (status, body) = get(&app, "/fibonacci/sequence/11").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "span_too_large");
+    }
+
+    #[tokio::test]
+    async fn modular_index_must_be_plain_digits() {
+        let app = app(Config {
+            max_index_digits: 5,
+            ..config()
+        });
+        let (status, body) = get(&app, "/fibonacci/12/mod/7").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"], "4");
+        for index in ["+12", "1_0", "-1", "0x10"] {
+            let (status, body) = get(&app, &format!("/fibonacci/{index}/mod/7")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{index}");
+            assert_eq!(body["error"]["code"], "invalid_n", "{index}");
+        }
+        let (status, body) = get(&app, "/fibonacci/123456/mod/7").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "n_too_large");
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use super::extract::{Json, Path};
+use crate::{deadline::Deadline, error::ApiError, kbonacci as engine, state::AppState};
+
+#[derive(Serialize)]
+pub struct KbonacciResponse {
+    k: usize,
+    n: u64,
+    result: String,
+    digits: usize,
+    exact: bool,
+}
+
+/// `GET /kbonacci/{k}/{n}`
+pub async fn kbonacci(
+    State(state): State<Arc<AppState>>,
+    Path((k, n)): Path<(usize, u64)>,
+    deadline: Deadline,
+) -> Result<Json<KbonacciResponse>, ApiError> {
+    let max_k = state.config.kbonacci_max_k;
+    if !(1..=max_k).contains(&k) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "k_out_of_range",
+            format!("k must be between 1 and {max_k}"),
+        ));
+    }
+    if n > state.config.max_n {
+        return Err(ApiError::n_out_of_range(n, state.config.max_n));
+    }
+    let result = state
+        .run(deadline, move |deadline| {
+            Ok(engine::kbonacci(k, n, deadline)?.to_string())
+        })
+        .await?;
+    Ok(Json(KbonacciResponse {
+        k,
+        n,
+        digits: result.len(),
+        result,
+        exact: true,
+    }))
+}
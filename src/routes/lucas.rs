@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+
+use super::extract::{Json, Path, Query};
+use super::fibonacci::{check_n, FibResponse};
+use crate::{
+    cache::{self, Key},
+    deadline::Deadline,
+    error::ApiError,
+    lucas as engine,
+    state::AppState,
+};
+
+/// `GET /lucas/{n}`: same shape as `/fibonacci/{n}`.
+pub async fn lucas(
+    State(state): State<Arc<AppState>>,
+    Path(n): Path<i64>,
+    deadline: Deadline,
+) -> Result<(cache::Status, Json<FibResponse>), ApiError> {
+    check_n(&state.config, n)?;
+    let (value, cache_status) = state
+        .term(Key::new("lucas", n, None), deadline, move |deadline| {
+            engine::lucas_within(n, deadline)
+        })
+        .await?;
+    Ok((cache_status, Json(FibResponse::exact(n, &value))))
+}
+
+#[derive(Deserialize)]
+pub struct SequenceQuery {
+    p: i64,
+    q: i64,
+    n: u64,
+}
+
+#[derive(Serialize)]
+pub struct SequenceResponse {
+    p: i64,
+    q: i64,
+    n: u64,
+    /// U_n(P, Q) as a decimal string.
+    u: String,
+    /// V_n(P, Q) as a decimal string.
+    v: String,
+    exact: bool,
+}
+
+/// `GET /lucas-sequence?p=&q=&n=`
+///
+/// The terms grow no faster than (|P| + |Q|)^n, so the bound is on their estimated
+/// size rather than on n alone.
+pub async fn sequence(
+    State(state): State<Arc<AppState>>,
+    Query(SequenceQuery { p, q, n }): Query<SequenceQuery>,
+    deadline: Deadline,
+) -> Result<Json<SequenceResponse>, ApiError> {
+    let bits = engine::uv_bits(p, q, n);
+    if bits > state.config.max_result_bits as f64 {
+        return Err(ApiError::result_too_large(
+            bits,
+            state.config.max_result_bits,
+        ));
+    }
+    let (u, v) = state
+        .run(deadline, move |deadline| {
+            let (u, v) = engine::lucas_uv(p, q, n, deadline)?;
+            Ok((u.to_string(), v.to_string()))
+        })
+        .await?;
+    Ok(Json(SequenceResponse {
+        p,
+        q,
+        n,
+        u,
+        v,
+        exact: true,
+    }))
+}
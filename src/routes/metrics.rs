@@ -0,0 +1,64 @@
+use std::{fmt::Write, sync::Arc};
+
+use axum::{extract::State, http::header};
+
+use crate::state::AppState;
+
+/// `GET /metrics`, in the Prometheus text exposition format.
+pub async fn metrics(
+    State(state): State<Arc<AppState>>,
+) -> ([(header::HeaderName, &'static str); 1], String) {
+    let cache = state.cache.stats();
+    let pool = state.pool.stats();
+    let mut body = String::new();
+    for (name, kind, help, value) in [
+        (
+            "argo_cache_hits_total",
+            "counter",
+            "Term lookups answered from the result cache.",
+            cache.hits,
+        ),
+        (
+            "argo_cache_misses_total",
+            "counter",
+            "Term lookups that had to be computed.",
+            cache.misses,
+        ),
+        (
+            "argo_cache_entries",
+            "gauge",
+            "Terms currently held in the result cache.",
+            cache.entries as u64,
+        ),
+        (
+            "argo_cache_bytes",
+            "gauge",
+            "Estimated size of the result cache in bytes.",
+            cache.bytes as u64,
+        ),
+        (
+            "argo_pool_running",
+            "gauge",
+            "Computations running on the worker pool.",
+            pool.running as u64,
+        ),
+        (
+            "argo_pool_queue_depth",
+            "gauge",
+            "Computations waiting for a worker.",
+            pool.queued as u64,
+        ),
+        (
+            "argo_pool_rejected_total",
+            "counter",
+            "Computations turned away with 503 because the pool was full.",
+            pool.rejected,
+        ),
+    ] {
+        let _ = writeln!(
+            body,
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}"
+        );
+    }
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::state::AppState;
+
+mod extract;
+mod fibcode;
+mod fibonacci;
+mod kbonacci;
+mod lucas;
+mod metrics;
+mod phi;
+mod pisano;
+mod recurrence;
+mod sequences;
+mod stream;
+mod ws;
+mod zeckendorf;
+
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/hello", get(hello))
+        .route("/fibcode/decode", post(fibcode::decode))
+        .route("/fibcode/encode", post(fibcode::encode))
+        .route("/fibonacci", get(fibonacci::range))
+        .route("/fibonacci/batch", post(fibonacci::batch))
+        .route("/fibonacci/gcd", get(fibonacci::gcd))
+        .route("/fibonacci/inverse/{value}", get(fibonacci::inverse))
+        .route("/fibonacci/primes", get(fibonacci::primes))
+        .route("/fibonacci/sequence/{count}", get(fibonacci::sequence))
+        .route("/fibonacci/sum", get(fibonacci::sum))
+        .route("/fibonacci/stream", get(stream::stream))
+        .route("/fibonacci/{n}", get(fibonacci::fibonacci))
+        .route("/fibonacci/{n}/mod/{m}", get(fibonacci::modulo))
+        .route("/fibonacci/{n}/ratio", get(phi::ratio))
+        .route("/kbonacci/{k}/{n}", get(kbonacci::kbonacci))
+        .route("/lucas/{n}", get(lucas::lucas))
+        .route("/lucas-sequence", get(lucas::sequence))
+        .route("/metrics", get(metrics::metrics))
+        .route("/phi", get(phi::phi))
+        .route("/pisano/{m}", get(pisano::pisano))
+        .route("/recurrence", post(recurrence::recurrence))
+        .route("/sequences", get(sequences::list))
+        .route("/sequences/{name}", get(sequences::range))
+        .route("/sequences/{name}/{n}", get(sequences::term))
+        .route("/ws", get(ws::ws))
+        .route("/zeckendorf/{value}", get(zeckendorf::zeckendorf))
+        .with_state(state)
+}
+
+#[derive(Serialize)]
+struct HelloResponse {
+    message: String,
+}
+
+async fn hello() -> Json<HelloResponse> {
+    Json(HelloResponse {
+        message: "Hello Dennis!".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{self, Body},
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::config::Config;
+
+    /// The configuration from the environment, without a store.
+    pub(super) fn config() -> Config {
+        Config {
+            store_dir: None,
+            ..Config::from_env()
+        }
+    }
+
+    pub(super) fn app(config: Config) -> Router {
+        router(Arc::new(AppState::new(config)))
+    }
+
+    /// Sends `request` through `app` and parses the response body as JSON.
+    pub(super) async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    pub(super) async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+        send(app, Request::get(uri).body(Body::empty()).unwrap()).await
+    }
+
+    pub(super) async fn post(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        send(app, request).await
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::extract::{Json, Path, Query};
+use super::fibonacci::check_n;
+use crate::{
+    config::Config, deadline::Deadline, error::ApiError, fib, phi as engine, state::AppState,
+};
+
+/// Decimal places returned when `digits` is not given.
+const DEFAULT_DIGITS: u32 = 50;
+
+#[derive(Deserialize)]
+pub struct DigitsQuery {
+    digits: Option<u32>,
+}
+
+fn digits(config: &Config, requested: Option<u32>) -> Result<u32, ApiError> {
+    let digits = requested.unwrap_or(DEFAULT_DIGITS);
+    if digits > config.phi_max_digits {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "digits_too_large",
+            format!(
+                "digits = {digits} exceeds the configured maximum of {}",
+                config.phi_max_digits
+            ),
+        ));
+    }
+    Ok(digits)
+}
+
+#[derive(Serialize)]
+pub struct PhiResponse {
+    digits: u32,
+    /// φ truncated to `digits` decimal places.
+    value: String,
+}
+
+/// `GET /phi?digits=N`
+pub async fn phi(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DigitsQuery>,
+    deadline: Deadline,
+) -> Result<Json<PhiResponse>, ApiError> {
+    let digits = digits(&state.config, query.digits)?;
+    let value = state
+        .run(deadline, move |deadline| {
+            deadline.check()?;
+            Ok(engine::phi(digits))
+        })
+        .await?;
+    Ok(Json(PhiResponse { digits, value }))
+}
+
+#[derive(Serialize)]
+pub struct RatioResponse {
+    n: i64,
+    /// F(n+1); consecutive Fibonacci numbers are coprime, so the fraction
+    /// is already in lowest terms.
+    numerator: String,
+    /// F(n).
+    denominator: String,
+    /// The ratio truncated to `digits` decimal places.
+    decimal: String,
+    /// Upper bound on |F(n+1)/F(n) − φ|, which is below 1/(F(n)·F(n+1)).
+    error_bound: String,
+    /// Convergents alternate around φ: even n lands above it.
+    above_phi: bool,
+}
+
+/// `GET /fibonacci/{n}/ratio?digits=N`, for n ≥ 1.
+pub async fn ratio(
+    State(state): State<Arc<AppState>>,
+    Path(n): Path<i64>,
+    Query(query): Query<DigitsQuery>,
+    deadline: Deadline,
+) -> Result<Json<RatioResponse>, ApiError> {
+    if n < 1 {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_n",
+            "the ratio F(n+1)/F(n) converges to φ only for n ≥ 1",
+        ));
+    }
+    check_n(&state.config, n)?;
+    let digits = digits(&state.config, query.digits)?;
+    let response = state
+        .run(deadline, move |deadline| {
+            let (den, num) = fib::fib_pair_within(n.unsigned_abs(), deadline)?;
+            deadline.check()?;
+            Ok(RatioResponse {
+                n,
+                decimal: engine::decimal(&num, &den, digits),
+                error_bound: engine::reciprocal_upper_bound(&(&num * &den)),
+                numerator: num.to_string(),
+                denominator: den.to_string(),
+                above_phi: n % 2 == 0,
+            })
+        })
+        .await?;
+    Ok(Json(response))
+}
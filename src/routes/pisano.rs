@@ -0,0 +1,78 @@
+use std::sync::{Arc, PoisonError};
+
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use super::extract::{Json, Path};
+use crate::{
+    deadline::Deadline,
+    error::ApiError,
+    pisano::{self as engine, Period},
+    state::AppState,
+};
+
+#[derive(Serialize)]
+pub struct Factor {
+    prime: u64,
+    exponent: u32,
+}
+
+#[derive(Serialize)]
+pub struct PisanoResponse {
+    modulus: u64,
+    /// Decimal string: π(m) can reach 6m, which exceeds a `u64`.
+    period: String,
+    factors: Vec<Factor>,
+}
+
+/// `GET /pisano/{m}`
+pub async fn pisano(
+    State(state): State<Arc<AppState>>,
+    Path(m): Path<String>,
+    deadline: Deadline,
+) -> Result<Json<PisanoResponse>, ApiError> {
+    let modulus = m.parse::<u64>().ok().filter(|m| *m > 0).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_modulus",
+            format!("modulus must be between 1 and {}", u64::MAX),
+        )
+    })?;
+    let cached = state
+        .pisano_cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&modulus)
+        .cloned();
+    let Period { period, factors } = match cached {
+        Some(period) => period,
+        None => {
+            let period = state
+                .run(deadline, move |deadline| engine::pisano(modulus, deadline))
+                .await?;
+            let mut cache = state
+                .pisano_cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if cache.len() >= state.config.pisano_cache_size {
+                // Periods are cheap to recompute; dropping an arbitrary
+                // entry keeps memory bounded without tracking recency.
+                if let Some(victim) = cache.keys().next().copied() {
+                    cache.remove(&victim);
+                }
+            }
+            if state.config.pisano_cache_size > 0 {
+                cache.insert(modulus, period.clone());
+            }
+            period
+        }
+    };
+    Ok(Json(PisanoResponse {
+        modulus,
+        period: period.to_string(),
+        factors: factors
+            .into_iter()
+            .map(|(prime, exponent)| Factor { prime, exponent })
+            .collect(),
+    }))
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+
+use super::extract::Json;
+use crate::{deadline::Deadline, error::ApiError, recurrence::Recurrence, state::AppState};
+
+#[derive(Deserialize)]
+pub struct RecurrenceRequest {
+    /// c_1, …, c_k in a_n = c_1·a_{n−1} + … + c_k·a_{n−k}.
+    coefficients: Vec<i64>,
+    /// a_0, …, a_{k−1}.
+    initial: Vec<i64>,
+    n: u64,
+    modulus: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct RecurrenceResponse {
+    n: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modulus: Option<u64>,
+    result: String,
+    /// Number of decimal digits, not counting a leading minus sign.
+    digits: usize,
+    exact: bool,
+}
+
+/// `POST /recurrence`
+///
+/// Without a modulus the estimated size of a_n is bounded by
+/// `MAX_RESULT_BITS`; with one, any `u64` index is cheap.
+pub async fn recurrence(
+    State(state): State<Arc<AppState>>,
+    deadline: Deadline,
+    Json(request): Json<RecurrenceRequest>,
+) -> Result<Json<RecurrenceResponse>, ApiError> {
+    let config = &state.config;
+    let order = request.coefficients.len();
+    if order == 0 || order != request.initial.len() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_recurrence",
+            "coefficients and initial must be non-empty and the same length",
+        ));
+    }
+    if order > config.recurrence_max_order {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "order_too_large",
+            format!(
+                "order {order} exceeds the configured maximum of {}",
+                config.recurrence_max_order
+            ),
+        ));
+    }
+    if request.modulus == Some(0) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_modulus",
+            format!("modulus must be between 1 and {}", u64::MAX),
+        ));
+    }
+    let recurrence = Recurrence {
+        coefficients: request.coefficients.into_iter().map(BigInt::from).collect(),
+        initial: request.initial.into_iter().map(BigInt::from).collect(),
+        modulus: request.modulus.map(BigInt::from),
+    };
+    let n = request.n;
+    if request.modulus.is_none() {
+        let bits = recurrence.bits(n);
+        if bits > config.max_result_bits as f64 {
+            return Err(ApiError::result_too_large(bits, config.max_result_bits));
+        }
+    }
+    let result = state
+        .run(deadline, move |deadline| {
+            Ok(recurrence.term(n, deadline)?.to_string())
+        })
+        .await?;
+    Ok(Json(RecurrenceResponse {
+        n,
+        modulus: request.modulus,
+        digits: result.trim_start_matches('-').len(),
+        result,
+        exact: true,
+    }))
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::extract::{Json, Path, Query};
+use super::fibonacci::{FibResponse, Term};
+use crate::{
+    cache::{self, Key},
+    deadline::Deadline,
+    error::ApiError,
+    sequences::{Metadata, Sequence},
+    state::AppState,
+};
+
+/// `GET /sequences`: every registered sequence, in name order.
+pub async fn list(State(state): State<Arc<AppState>>) -> Json<Vec<&'static Metadata>> {
+    Json(state.sequences.iter().map(|s| s.metadata()).collect())
+}
+
+/// `GET /sequences/{name}/{n}`: same shape as `/fibonacci/{n}`.
+pub async fn term(
+    State(state): State<Arc<AppState>>,
+    Path((name, n)): Path<(String, u64)>,
+    deadline: Deadline,
+) -> Result<(cache::Status, Json<FibResponse>), ApiError> {
+    let sequence = lookup(&state, &name)?;
+    check_index(&state, &*sequence, n)?;
+    let key = Key::new(sequence.metadata().name, n, None);
+    let (value, cache_status) = state
+        .term(key, deadline, move |deadline| sequence.term(n, deadline))
+        .await?;
+    Ok((cache_status, Json(FibResponse::exact(n as i64, &value))))
+}
+
+#[derive(Deserialize)]
+pub struct RangeQuery {
+    from: u64,
+    /// Inclusive upper bound.
+    to: u64,
+}
+
+#[derive(Serialize)]
+pub struct RangeResponse {
+    name: &'static str,
+    items: Vec<Term>,
+}
+
+/// `GET /sequences/{name}?from=a&to=b`, bounded like `/fibonacci`.
+pub async fn range(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(RangeQuery { from, to }): Query<RangeQuery>,
+    deadline: Deadline,
+) -> Result<Json<RangeResponse>, ApiError> {
+    let sequence = lookup(&state, &name)?;
+    if from > to {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_range",
+            format!("from = {from} is greater than to = {to}"),
+        ));
+    }
+    check_index(&state, &*sequence, from)?;
+    check_index(&state, &*sequence, to)?;
+    if to - from >= state.config.max_span {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "span_too_large",
+            format!(
+                "range of {} terms exceeds the configured maximum of {}",
+                to - from + 1,
+                state.config.max_span
+            ),
+        ));
+    }
+    let terms = {
+        let sequence = Arc::clone(&sequence);
+        state
+            .run(deadline, move |deadline| sequence.range(from, to, deadline))
+            .await?
+    };
+    let items = (from..)
+        .zip(terms)
+        .map(|(n, value)| Term::new(n as i64, &value))
+        .collect();
+    Ok(Json(RangeResponse {
+        name: sequence.metadata().name,
+        items,
+    }))
+}
+
+fn lookup(state: &AppState, name: &str) -> Result<Arc<dyn Sequence>, ApiError> {
+    state.sequences.get(name).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "unknown_sequence",
+            format!("no sequence named {name:?}"),
+        )
+    })
+}
+
+/// Rejects indices below the sequence's offset or above the smaller of its
+/// own limit and `FIB_MAX_N`.
+fn check_index(state: &AppState, sequence: &dyn Sequence, n: u64) -> Result<(), ApiError> {
+    let metadata = sequence.metadata();
+    let max_n = metadata
+        .max_n
+        .map_or(state.config.max_n, |max| max.min(state.config.max_n));
+    if n < metadata.offset || n > max_n {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "n_out_of_range",
+            format!(
+                "n = {n} is outside the range {}..={max_n} of {}",
+                metadata.offset, metadata.name
+            ),
+        ));
+    }
+    Ok(())
+}
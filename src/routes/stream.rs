@@ -0,0 +1,134 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use tokio::time::{self, Interval, MissedTickBehavior};
+
+use super::extract::Query;
+use super::fibonacci::{check_n, FibResponse};
+use crate::{config::Config, deadline::Deadline, error::ApiError, fib, state::AppState};
+
+#[derive(Deserialize)]
+pub struct StreamQuery {
+    /// First index to emit when not resuming.
+    #[serde(default)]
+    start: i64,
+    /// Events per second, capped at `FIB_STREAM_MAX_RATE`.
+    rate: Option<u32>,
+}
+
+/// `GET /fibonacci/stream`: emits F(start), F(start+1), … as SSE events
+/// whose id is the index, until `FIB_MAX_N` is reached.
+///
+/// Terms are produced lazily as the response body is polled, so a slow
+/// client holds back computation instead of buffering events, and a
+/// disconnect drops the stream. A reconnecting client's `Last-Event-ID`
+/// resumes from the following index. Only the starting pair needs fast
+/// doubling; it is computed on the worker pool under the request deadline.
+pub async fn stream(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<StreamQuery>,
+    deadline: Deadline,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let start = match headers.get("last-event-id") {
+        None => query.start,
+        Some(id) => id
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse::<i64>().ok())
+            .and_then(|id| id.checked_add(1))
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_last_event_id",
+                    "Last-Event-ID must be an index previously sent by this stream",
+                )
+            })?,
+    };
+    check_n(&state.config, start)?;
+    let max_n = state.config.max_n;
+    let terms = state
+        .run(deadline, move |deadline| fib::terms_within(start, deadline))
+        .await?;
+    let ticks = ticker(&state.config, query.rate);
+    let events = stream::unfold((terms, ticks), move |(mut terms, mut ticks)| async move {
+        ticks.tick().await;
+        let (n, value) = terms.next().filter(|(n, _)| n.unsigned_abs() <= max_n)?;
+        let event = Event::default()
+            .id(n.to_string())
+            .json_data(FibResponse::exact(n, &value));
+        Some((event, (terms, ticks)))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Paces a feed at `rate` terms per second, capped at `FIB_STREAM_MAX_RATE`.
+pub fn ticker(config: &Config, rate: Option<u32>) -> Interval {
+    let rate = rate
+        .unwrap_or(config.stream_max_rate)
+        .clamp(1, config.stream_max_rate);
+    let mut ticks = time::interval(Duration::from_secs(1) / rate);
+    // A client that stalls gets the next term when it resumes reading,
+    // not a burst of everything it missed.
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticks
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{self, Body},
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use super::super::tests::{app, config, get};
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn resumes_after_the_last_event_id() {
+        let app = app(Config {
+            max_n: 12,
+            stream_max_rate: 1_000,
+            ..config()
+        });
+        let request = Request::get("/fibonacci/stream?start=0")
+            .header("last-event-id", "9")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        // The stream ends at FIB_MAX_N, so the whole body can be read.
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let ids: Vec<_> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("id: "))
+            .collect();
+        assert_eq!(ids, ["10", "11", "12"]);
+        assert!(body.contains(r#""n":12,"result":"144""#), "{body}");
+    }
+
+    #[tokio::test]
+    async fn bad_starting_points_are_rejected() {
+        let app = app(config());
+        let request = Request::get("/fibonacci/stream")
+            .header("last-event-id", "x")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (status, body) = get(&app, "/fibonacci/stream?start=-100001").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "n_out_of_range");
+    }
+}
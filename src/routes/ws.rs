@@ -0,0 +1,393 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::StatusCode,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use super::{
+    fibonacci::{check_n, check_range, FibResponse},
+    stream::ticker,
+};
+use crate::{
+    cache::Key,
+    deadline::Deadline,
+    error::{ApiError, ErrorBody},
+    fib,
+    state::AppState,
+};
+
+/// Live subscriptions a single connection may hold at once.
+const MAX_SUBSCRIPTIONS: usize = 16;
+
+/// A client message: an `op` with its fields, plus an optional `id` that is
+/// echoed on every reply it produces so clients can pipeline requests. For
+/// `unsubscribe` the `id` names the subscription.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Op {
+    Fib {
+        n: i64,
+    },
+    Range {
+        from: i64,
+        to: i64,
+    },
+    Subscribe {
+        #[serde(default)]
+        start: i64,
+        rate: Option<u32>,
+    },
+    Unsubscribe,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    id: Value,
+    #[serde(flatten)]
+    body: ReplyBody,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ReplyBody {
+    Term(FibResponse),
+    /// Marks the end of a `range` or `subscribe` reply sequence.
+    Done {
+        done: bool,
+    },
+    Error {
+        error: ErrorBody,
+    },
+}
+
+impl Reply {
+    fn term(id: &Value, n: i64, value: &num_bigint::BigInt) -> Self {
+        Self {
+            id: id.clone(),
+            body: ReplyBody::Term(FibResponse::exact(n, value)),
+        }
+    }
+
+    fn done(id: Value) -> Self {
+        Self {
+            id,
+            body: ReplyBody::Done { done: true },
+        }
+    }
+
+    fn error(id: Value, error: ApiError) -> Self {
+        Self {
+            id,
+            body: ReplyBody::Error { error: error.body },
+        }
+    }
+}
+
+/// `GET /ws`: JSON request/reply protocol over a single WebSocket.
+pub async fn ws(State(state): State<Arc<AppState>>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| Session::new(state).run(socket))
+}
+
+struct Session {
+    state: Arc<AppState>,
+    /// Replies produced by subscription tasks. Bounded, so a client that
+    /// stops reading stalls its subscriptions rather than growing a queue.
+    tx: mpsc::Sender<Reply>,
+    rx: mpsc::Receiver<Reply>,
+    subscriptions: HashMap<String, JoinHandle<()>>,
+}
+
+impl Session {
+    fn new(state: Arc<AppState>) -> Self {
+        let (tx, rx) = mpsc::channel(64);
+        Self {
+            state,
+            tx,
+            rx,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    async fn run(mut self, mut socket: WebSocket) {
+        loop {
+            let sent = tokio::select! {
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Text(text))) => self.handle(&mut socket, &text).await,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => Ok(()),
+                },
+                Some(reply) = self.rx.recv() => send(&mut socket, &reply).await,
+            };
+            if sent.is_err() {
+                break;
+            }
+        }
+        for (_, task) in self.subscriptions.drain() {
+            task.abort();
+        }
+    }
+
+    async fn handle(&mut self, socket: &mut WebSocket, text: &str) -> Result<(), axum::Error> {
+        let invalid = |err: serde_json::Error| {
+            ApiError::new(StatusCode::BAD_REQUEST, "invalid_message", err.to_string())
+        };
+        let mut message: Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(err) => return send(socket, &Reply::error(Value::Null, invalid(err))).await,
+        };
+        // Taken out before the op is parsed, so a malformed op is still
+        // reported against the request that sent it.
+        let id = message
+            .as_object_mut()
+            .and_then(|fields| fields.remove("id"))
+            .unwrap_or_default();
+        let op: Op = match serde_json::from_value(message) {
+            Ok(op) => op,
+            Err(err) => return send(socket, &Reply::error(id, invalid(err))).await,
+        };
+        let config = &self.state.config;
+        // Messages have no headers or query of their own, so each gets the
+        // server's default budget.
+        let deadline = Deadline::after(Duration::from_millis(config.request_timeout_ms));
+        match op {
+            Op::Fib { n } => {
+                let reply = match self.fib(n, deadline).await {
+                    Ok(value) => Reply::term(&id, n, &value),
+                    Err(error) => Reply::error(id, error),
+                };
+                send(socket, &reply).await
+            }
+            Op::Range { from, to } => {
+                if let Err(error) = check_range(config, from, to) {
+                    return send(socket, &Reply::error(id, error)).await;
+                }
+                let terms = self
+                    .state
+                    .run(deadline, move |deadline| {
+                        let mut terms = Vec::new();
+                        for term in fib::terms_within(from, deadline)?.take_while(|(n, _)| *n <= to)
+                        {
+                            deadline.check()?;
+                            terms.push(term);
+                        }
+                        Ok(terms)
+                    })
+                    .await;
+                match terms {
+                    Ok(terms) => {
+                        for (n, value) in terms {
+                            send(socket, &Reply::term(&id, n, &value)).await?;
+                        }
+                        send(socket, &Reply::done(id)).await
+                    }
+                    Err(error) => send(socket, &Reply::error(id, error)).await,
+                }
+            }
+            Op::Subscribe { start, rate } => {
+                match self.subscribe(id.clone(), start, rate, deadline).await {
+                    Ok(()) => Ok(()),
+                    Err(error) => send(socket, &Reply::error(id, error)).await,
+                }
+            }
+            Op::Unsubscribe => {
+                let reply = match self.subscriptions.remove(&id.to_string()) {
+                    Some(task) => {
+                        task.abort();
+                        Reply::done(id)
+                    }
+                    None => Reply::error(
+                        id,
+                        ApiError::new(
+                            StatusCode::NOT_FOUND,
+                            "unknown_subscription",
+                            "no subscription with this id",
+                        ),
+                    ),
+                };
+                send(socket, &reply).await
+            }
+        }
+    }
+
+    async fn fib(&self, n: i64, deadline: Deadline) -> Result<Arc<num_bigint::BigInt>, ApiError> {
+        check_n(&self.state.config, n)?;
+        let (value, _) = self
+            .state
+            .term(Key::new("fibonacci", n, None), deadline, move |deadline| {
+                fib::fib_within(n, deadline)
+            })
+            .await?;
+        Ok(value)
+    }
+
+    async fn subscribe(
+        &mut self,
+        id: Value,
+        start: i64,
+        rate: Option<u32>,
+        deadline: Deadline,
+    ) -> Result<(), ApiError> {
+        check_n(&self.state.config, start)?;
+        let max_n = self.state.config.max_n;
+        self.subscriptions.retain(|_, task| !task.is_finished());
+        let key = id.to_string();
+        if self.subscriptions.contains_key(&key) {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "duplicate_subscription",
+                "a subscription with this id is already active",
+            ));
+        }
+        if self.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+            return Err(ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "too_many_subscriptions",
+                format!("at most {MAX_SUBSCRIPTIONS} subscriptions per connection"),
+            ));
+        }
+        // Only the starting pair needs fast doubling; later terms are one
+        // addition each and are produced at the subscription's pace.
+        let terms = self
+            .state
+            .run(deadline, move |deadline| fib::terms_within(start, deadline))
+            .await?;
+        let mut ticks = ticker(&self.state.config, rate);
+        let tx = self.tx.clone();
+        let task = tokio::spawn(async move {
+            for (n, value) in terms.take_while(|(n, _)| n.unsigned_abs() <= max_n) {
+                ticks.tick().await;
+                if tx.send(Reply::term(&id, n, &value)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(Reply::done(id)).await;
+        });
+        self.subscriptions.insert(key, task);
+        Ok(())
+    }
+}
+
+async fn send(socket: &mut WebSocket, reply: &Reply) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(reply).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text.into())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::json;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::{tungstenite, MaybeTlsStream, WebSocketStream};
+
+    use super::super::{router, tests::config};
+    use super::*;
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    /// Serves the router on a local port and opens a WebSocket to `/ws`.
+    async fn connect() -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router(Arc::new(AppState::new(config())));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let (client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws"))
+            .await
+            .unwrap();
+        client
+    }
+
+    async fn request(client: &mut Client, message: Value) {
+        let text = message.to_string();
+        client.send(tungstenite::Message::text(text)).await.unwrap();
+    }
+
+    /// The next reply matching `wanted`, skipping subscription ticks.
+    async fn reply(client: &mut Client, wanted: impl Fn(&Value) -> bool) -> Value {
+        loop {
+            let message = client.next().await.unwrap().unwrap();
+            let reply: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            if wanted(&reply) {
+                return reply;
+            }
+        }
+    }
+
+    fn with_id(id: Value) -> impl Fn(&Value) -> bool {
+        move |reply| reply["id"] == id
+    }
+
+    #[tokio::test]
+    async fn answers_fib_and_range_requests() {
+        let mut client = connect().await;
+        request(&mut client, json!({"op": "fib", "n": 10, "id": 1})).await;
+        assert_eq!(
+            reply(&mut client, |_| true).await,
+            json!({"id": 1, "n": 10, "result": "55", "digits": 2, "exact": true})
+        );
+
+        request(
+            &mut client,
+            json!({"op": "range", "from": -1, "to": 1, "id": "r"}),
+        )
+        .await;
+        let mut results = Vec::new();
+        loop {
+            let reply = reply(&mut client, |_| true).await;
+            assert_eq!(reply["id"], "r");
+            if reply["done"] == true {
+                break;
+            }
+            results.push(reply["result"].clone());
+        }
+        assert_eq!(results, [json!("1"), json!("0"), json!("1")]);
+
+        request(&mut client, json!({"op": "nope", "id": 7})).await;
+        let error = reply(&mut client, |_| true).await;
+        assert_eq!(error["id"], 7);
+        assert_eq!(error["error"]["code"], "invalid_message");
+    }
+
+    #[tokio::test]
+    async fn subscriptions_tick_until_unsubscribed() {
+        let mut client = connect().await;
+        request(
+            &mut client,
+            json!({"op": "subscribe", "start": 5, "id": "s"}),
+        )
+        .await;
+        let first = reply(&mut client, with_id(json!("s"))).await;
+        assert_eq!((&first["n"], &first["result"]), (&json!(5), &json!("5")));
+        let second = reply(&mut client, with_id(json!("s"))).await;
+        assert_eq!((&second["n"], &second["result"]), (&json!(6), &json!("8")));
+
+        request(&mut client, json!({"op": "subscribe", "id": "s"})).await;
+        let error = reply(&mut client, |reply| reply.get("error").is_some()).await;
+        assert_eq!(error["id"], "s");
+        assert_eq!(error["error"]["code"], "duplicate_subscription");
+
+        request(&mut client, json!({"op": "unsubscribe", "id": "s"})).await;
+        reply(&mut client, |reply| reply["done"] == true).await;
+        request(&mut client, json!({"op": "unsubscribe", "id": "s"})).await;
+        let error = reply(&mut client, |reply| reply.get("error").is_some()).await;
+        assert_eq!(error["error"]["code"], "unknown_subscription");
+    }
+
+    #[tokio::test]
+    async fn limits_subscriptions_per_connection() {
+        let mut client = connect().await;
+        for id in 0..=MAX_SUBSCRIPTIONS {
+            request(&mut client, json!({"op": "subscribe", "rate": 1, "id": id})).await;
+        }
+        let error = reply(&mut client, |reply| reply.get("error").is_some()).await;
+        assert_eq!(error["id"], MAX_SUBSCRIPTIONS);
+        assert_eq!(error["error"]["code"], "too_many_subscriptions");
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode};
+use num_bigint::Sign;
+use serde::Serialize;
+
+use super::extract::{Json, Path};
+use super::fibonacci::parse_value;
+use crate::{deadline::Deadline, error::ApiError, state::AppState, zeckendorf as engine};
+
+#[derive(Serialize)]
+pub struct ZeckendorfTerm {
+    index: u64,
+    value: String,
+}
+
+#[derive(Serialize)]
+pub struct ZeckendorfResponse {
+    value: String,
+    /// Largest term first.
+    terms: Vec<ZeckendorfTerm>,
+    /// Fibonacci code: one bit per F(2), F(3), … plus a terminating `1`.
+    code: String,
+}
+
+/// `GET /zeckendorf/{value}`
+pub async fn zeckendorf(
+    State(state): State<Arc<AppState>>,
+    Path(raw): Path<String>,
+    deadline: Deadline,
+) -> Result<Json<ZeckendorfResponse>, ApiError> {
+    let value = parse_value(&state.config, &raw)?;
+    if value.sign() != Sign::Plus {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_value",
+            "value must be a positive integer",
+        ));
+    }
+    let response = state
+        .run(deadline, move |deadline| {
+            deadline.check()?;
+            let terms = engine::zeckendorf(value.magnitude());
+            Ok(ZeckendorfResponse {
+                value: value.to_string(),
+                code: engine::code(&terms),
+                terms: terms
+                    .into_iter()
+                    .map(|(index, value)| ZeckendorfTerm {
+                        index,
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            })
+        })
+        .await?;
+    Ok(Json(response))
+}
@@ -0,0 +1,42 @@
+use num_bigint::BigInt;
+use num_traits::One;
+
+use super::{Growth, Metadata, Sequence};
+use crate::deadline::{Deadline, Expired};
+
+pub struct Catalan;
+
+const METADATA: Metadata = Metadata {
+    name: "catalan",
+    description: "Catalan numbers: C(n) = (2n)! / (n! (n+1)!)",
+    oeis: "A000108",
+    growth: Growth::Exponential,
+    offset: 0,
+    // Each term costs n big-integer steps, unlike the O(log n) recurrences.
+    max_n: Some(10_000),
+};
+
+impl Sequence for Catalan {
+    fn metadata(&self) -> &'static Metadata {
+        &METADATA
+    }
+
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        Ok(self.range(n, n, deadline)?.pop().unwrap_or_default())
+    }
+
+    /// Walks C(k+1) = C(k)·2(2k+1)/(k+2) up from C(0) = 1; the division is
+    /// always exact.
+    fn range(&self, from: u64, to: u64, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        let mut value = BigInt::one();
+        let mut terms = Vec::with_capacity((to - from + 1) as usize);
+        for k in 0..=to {
+            deadline.check()?;
+            if k >= from {
+                terms.push(value.clone());
+            }
+            value = value * (2 * (2 * k + 1)) / (k + 2);
+        }
+        Ok(terms)
+    }
+}
@@ -0,0 +1,38 @@
+use num_bigint::BigInt;
+
+use super::{Growth, Metadata, Sequence};
+use crate::{
+    deadline::{Deadline, Expired},
+    fib,
+};
+
+pub struct Fibonacci;
+
+const METADATA: Metadata = Metadata {
+    name: "fibonacci",
+    description: "Fibonacci numbers: F(n) = F(n−1) + F(n−2), F(0) = 0, F(1) = 1",
+    oeis: "A000045",
+    growth: Growth::Exponential,
+    offset: 0,
+    max_n: None,
+};
+
+impl Sequence for Fibonacci {
+    fn metadata(&self) -> &'static Metadata {
+        &METADATA
+    }
+
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        Ok(fib::fib_pair_within(n, deadline)?.0.into())
+    }
+
+    fn range(&self, from: u64, to: u64, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        let count = (to - from + 1) as usize;
+        let mut terms = Vec::with_capacity(count);
+        for (_, value) in fib::terms_within(from as i64, deadline)?.take(count) {
+            deadline.check()?;
+            terms.push(value);
+        }
+        Ok(terms)
+    }
+}
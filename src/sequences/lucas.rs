@@ -0,0 +1,39 @@
+use num_bigint::BigInt;
+
+use super::{Growth, Metadata, Sequence};
+use crate::{
+    deadline::{Deadline, Expired},
+    lucas,
+};
+
+pub struct Lucas;
+
+const METADATA: Metadata = Metadata {
+    name: "lucas",
+    description: "Lucas numbers: L(n) = L(n−1) + L(n−2), L(0) = 2, L(1) = 1",
+    oeis: "A000032",
+    growth: Growth::Exponential,
+    offset: 0,
+    max_n: None,
+};
+
+impl Sequence for Lucas {
+    fn metadata(&self) -> &'static Metadata {
+        &METADATA
+    }
+
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        lucas::lucas_within(n as i64, deadline)
+    }
+
+    fn range(&self, from: u64, to: u64, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        let (mut a, mut b) = (self.term(from, deadline)?, self.term(from + 1, deadline)?);
+        let mut terms = Vec::with_capacity((to - from + 1) as usize);
+        for _ in from..=to {
+            deadline.check()?;
+            let next = &a + &b;
+            terms.push(std::mem::replace(&mut a, std::mem::replace(&mut b, next)));
+        }
+        Ok(terms)
+    }
+}
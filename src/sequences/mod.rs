@@ -0,0 +1,129 @@
+//! Integer sequences served generically under `/sequences`.
+//!
+//! Each sequence lives in its own module and implements [`Sequence`]; adding
+//! one means writing that module and registering it in
+//! [`Registry::default`].
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use num_bigint::BigInt;
+use serde::Serialize;
+
+use crate::deadline::{Deadline, Expired};
+
+mod catalan;
+mod fibonacci;
+mod lucas;
+mod pell;
+mod primes;
+
+/// How fast a sequence's terms grow, which bounds what it costs to serve.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Growth {
+    /// Θ(n log n), e.g. the primes.
+    Quasilinear,
+    /// Θ(c^n) up to polynomial factors.
+    Exponential,
+}
+
+/// Static description of a sequence, listed by `GET /sequences`.
+#[derive(Serialize)]
+pub struct Metadata {
+    /// Name used in the URL.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// OEIS A-number.
+    pub oeis: &'static str,
+    pub growth: Growth,
+    /// First valid index, following the OEIS offset.
+    pub offset: u64,
+    /// Largest index the sequence serves, if tighter than `FIB_MAX_N`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_n: Option<u64>,
+}
+
+pub trait Sequence: Send + Sync {
+    fn metadata(&self) -> &'static Metadata;
+
+    /// The term at index `n`, where `offset ≤ n ≤ max_n`. Long-running
+    /// implementations should call `deadline.check()` as they go.
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired>;
+
+    /// Terms `from..=to`. Override when consecutive terms are cheaper to
+    /// produce together than one at a time.
+    fn range(&self, from: u64, to: u64, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        (from..=to).map(|n| self.term(n, deadline)).collect()
+    }
+}
+
+/// Registered sequences by name.
+pub struct Registry {
+    sequences: BTreeMap<&'static str, Arc<dyn Sequence>>,
+}
+
+impl Registry {
+    pub fn register(&mut self, sequence: impl Sequence + 'static) {
+        self.sequences
+            .insert(sequence.metadata().name, Arc::new(sequence));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Sequence>> {
+        self.sequences.get(name).cloned()
+    }
+
+    /// All sequences, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Sequence> {
+        self.sequences.values().map(Arc::as_ref)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self {
+            sequences: BTreeMap::new(),
+        };
+        registry.register(catalan::Catalan);
+        registry.register(fibonacci::Fibonacci);
+        registry.register(lucas::Lucas);
+        registry.register(pell::Pell);
+        registry.register(primes::Primes);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_terms_match_oeis() {
+        let registry = Registry::default();
+        for (name, expected) in [
+            ("catalan", vec![1, 1, 2, 5, 14, 42, 132, 429]),
+            ("fibonacci", vec![0, 1, 1, 2, 3, 5, 8, 13]),
+            ("lucas", vec![2, 1, 3, 4, 7, 11, 18, 29]),
+            ("pell", vec![0, 1, 2, 5, 12, 29, 70, 169]),
+            ("primes", vec![2, 3, 5, 7, 11, 13, 17, 19]),
+        ] {
+            let sequence = registry.get(name).unwrap();
+            let from = sequence.metadata().offset;
+            let expected: Vec<BigInt> = expected.into_iter().map(BigInt::from).collect();
+            let terms = sequence.range(from, from + 7, &Deadline::none()).unwrap();
+            assert_eq!(terms, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn range_matches_term() {
+        for sequence in Registry::default().iter() {
+            let from = sequence.metadata().offset + 95;
+            let deadline = Deadline::none();
+            let terms = sequence.range(from, from + 10, &deadline).unwrap();
+            for (n, value) in (from..).zip(terms) {
+                let name = sequence.metadata().name;
+                assert_eq!(sequence.term(n, &deadline).unwrap(), value, "{name}({n})");
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use num_bigint::BigInt;
+
+use super::{Growth, Metadata, Sequence};
+use crate::{
+    deadline::{Deadline, Expired},
+    lucas,
+};
+
+pub struct Pell;
+
+const METADATA: Metadata = Metadata {
+    name: "pell",
+    description: "Pell numbers: P(n) = 2P(n−1) + P(n−2), P(0) = 0, P(1) = 1",
+    oeis: "A000129",
+    growth: Growth::Exponential,
+    offset: 0,
+    max_n: None,
+};
+
+impl Sequence for Pell {
+    fn metadata(&self) -> &'static Metadata {
+        &METADATA
+    }
+
+    /// P(n) = U_n(2, −1).
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        Ok(lucas::lucas_uv(2, -1, n, deadline)?.0)
+    }
+}
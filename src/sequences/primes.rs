@@ -0,0 +1,62 @@
+use num_bigint::BigInt;
+
+use super::{Growth, Metadata, Sequence};
+use crate::deadline::{Deadline, Expired};
+
+pub struct Primes;
+
+const METADATA: Metadata = Metadata {
+    name: "primes",
+    description: "The prime numbers, p(1) = 2",
+    oeis: "A000040",
+    growth: Growth::Quasilinear,
+    offset: 1,
+    // Keeps the sieve for the largest term under 16 MB.
+    max_n: Some(1_000_000),
+};
+
+impl Sequence for Primes {
+    fn metadata(&self) -> &'static Metadata {
+        &METADATA
+    }
+
+    fn term(&self, n: u64, deadline: &Deadline) -> Result<BigInt, Expired> {
+        Ok(self.range(n, n, deadline)?.pop().unwrap_or_default())
+    }
+
+    fn range(&self, from: u64, to: u64, deadline: &Deadline) -> Result<Vec<BigInt>, Expired> {
+        Ok(sieve(upper_bound(to), deadline)?
+            .into_iter()
+            .skip((from - 1) as usize)
+            .take((to - from + 1) as usize)
+            .map(BigInt::from)
+            .collect())
+    }
+}
+
+/// An upper bound on the nth prime: n(ln n + ln ln n) for n ≥ 6 (Rosser).
+fn upper_bound(n: u64) -> u64 {
+    if n < 6 {
+        return 13;
+    }
+    let n = n as f64;
+    (n * (n.ln() + n.ln().ln())).ceil() as u64
+}
+
+/// Primes up to and including `limit`, by the sieve of Eratosthenes.
+fn sieve(limit: u64, deadline: &Deadline) -> Result<Vec<u64>, Expired> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for i in 2..=limit {
+        if composite[i] {
+            continue;
+        }
+        deadline.check()?;
+        primes.push(i as u64);
+        for multiple in (i * i..=limit).step_by(i) {
+            composite[multiple] = true;
+        }
+    }
+    Ok(primes)
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use num_bigint::BigInt;
+
+use crate::{
+    cache::{self, Cache, Key},
+    config::Config,
+    deadline::{Deadline, Expired},
+    error::ApiError,
+    pisano,
+    pool::Pool,
+    sequences::Registry,
+    store::Store,
+};
+
+/// Shared state handed to every handler.
+pub struct AppState {
+    pub config: Config,
+    /// π(m) and the factors of m by modulus, bounded by
+    /// `config.pisano_cache_size`.
+    pub pisano_cache: Mutex<HashMap<u64, pisano::Period>>,
+    /// Computed terms, bounded by `config.result_cache_bytes`.
+    pub cache: Cache,
+    /// Large terms on disk, when `config.store_dir` is set.
+    pub store: Option<Store>,
+    /// Where cache misses are computed, off the async workers.
+    pub pool: Pool,
+    /// Sequences served under `/sequences`.
+    pub sequences: Registry,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Self {
+        let store = config.store_dir.as_ref().map(|dir| {
+            Store::open(dir, config.store_max_bytes)
+                .unwrap_or_else(|err| panic!("cannot open store at {}: {err}", dir.display()))
+        });
+        Self {
+            cache: Cache::new(config.result_cache_bytes),
+            store,
+            pool: Pool::new(config.worker_threads, config.worker_queue),
+            config,
+            pisano_cache: Mutex::new(HashMap::new()),
+            sequences: Registry::default(),
+        }
+    }
+
+    /// Runs `job` on the worker pool under `deadline`. The job sees the
+    /// deadline cancelled if the caller is dropped, e.g. because the client
+    /// disconnected, and waiting in the queue counts against the budget.
+    pub async fn run<T, F>(&self, deadline: Deadline, job: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Deadline) -> Result<T, Expired> + Send + 'static,
+    {
+        let guard = deadline.cancel_on_drop();
+        let task = self.pool.run({
+            let deadline = deadline.clone();
+            move || job(&deadline)
+        });
+        let result = match deadline.instant() {
+            Some(at) => tokio::time::timeout_at(at.into(), task)
+                .await
+                .map_err(|_| Expired)?,
+            None => task.await,
+        };
+        guard.disarm();
+        Ok(result??)
+    }
+
+    /// Looks a term up in the memory cache, then the on-disk store, and
+    /// only computes it if neither has it. The store and the computation
+    /// run on the worker pool, so a full pool fails with 503 and an
+    /// expired deadline with 504.
+    pub async fn term(
+        self: &Arc<Self>,
+        key: Key,
+        deadline: Deadline,
+        compute: impl FnOnce(&Deadline) -> Result<BigInt, Expired> + Send + 'static,
+    ) -> Result<(Arc<BigInt>, cache::Status), ApiError> {
+        if let Some(value) = self.cache.get(&key) {
+            return Ok((value, cache::Status::Hit));
+        }
+        let state = Arc::clone(self);
+        let stored = key.clone();
+        let value = self
+            .run(deadline, move |deadline| match &state.store {
+                Some(store) => match store.get(&stored) {
+                    Some(value) => Ok(value),
+                    None => {
+                        let value = compute(deadline)?;
+                        store.put(&stored, &value);
+                        Ok(value)
+                    }
+                },
+                None => compute(deadline),
+            })
+            .await?;
+        Ok(self.cache.insert(key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    use super::*;
+    use crate::fib;
+
+    /// One worker, no queue, no store.
+    fn state() -> Arc<AppState> {
+        Arc::new(AppState::new(Config {
+            worker_threads: 1,
+            worker_queue: 0,
+            store_dir: None,
+            result_cache_bytes: 1 << 20,
+            ..Config::from_env()
+        }))
+    }
+
+    #[tokio::test]
+    async fn full_pool_rejects_with_retry_after() {
+        let state = state();
+        let (release, wait) = mpsc::channel::<()>();
+        let blocked = {
+            let state = Arc::clone(&state);
+            tokio::spawn(async move {
+                state
+                    .run(Deadline::none(), move |_| Ok(wait.recv().is_ok()))
+                    .await
+            })
+        };
+        while state.pool.stats().running < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        let key = Key::new("fibonacci", 90, None);
+        let err = state
+            .term(key.clone(), Deadline::none(), |_| unreachable!())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::SERVICE_UNAVAILABLE);
+        let response = err.into_response();
+        assert_eq!(response.headers()["retry-after"], "1");
+
+        release.send(()).unwrap();
+        assert!(blocked.await.unwrap().unwrap());
+        let compute = |deadline: &Deadline| fib::fib_within(90, deadline);
+        let (value, status) = state
+            .term(key.clone(), Deadline::none(), compute)
+            .await
+            .unwrap();
+        assert_eq!(*value, fib::fib(90));
+        assert_eq!(status, cache::Status::Miss);
+        let (_, status) = state.term(key, Deadline::none(), compute).await.unwrap();
+        assert_eq!(status, cache::Status::Hit);
+    }
+
+    #[tokio::test]
+    async fn expired_deadline_times_out() {
+        let state = state();
+        let err = state
+            .term(
+                Key::new("fibonacci", 90, None),
+                Deadline::after(Duration::ZERO),
+                |deadline| fib::fib_within(90, deadline),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(state.cache.stats().entries, 0);
+
+        // A job that keeps checking is cut off once the deadline passes.
+        let spin = |deadline: &Deadline| -> Result<(), Expired> {
+            loop {
+                deadline.check()?;
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        };
+        let err = state
+            .run(Deadline::after(Duration::from_millis(20)), spin)
+            .await
+            .unwrap_err();
+        assert_eq!(err.body.code, "deadline_exceeded");
+    }
+}
@@ -0,0 +1,348 @@
+//! Optional on-disk store of large computed terms, so they survive restarts.
+//!
+//! Each term is one file under the configured directory, named by a hash
+//! of its key so that indices of any length make valid file names:
+//!
+//! ```text
+//! magic "FIBS" | version u8 | crc32 u32 | key length u16 | key | sign u8 | magnitude
+//! ```
+//!
+//! Integers are little-endian and the magnitude runs to the end of the file.
+//! The CRC covers everything after itself, so a torn or corrupted file is
+//! detected, deleted and recomputed. Files are written to a temporary name
+//! and renamed into place; temporaries left by a crash are removed on
+//! open. When the directory grows past its cap, the least recently written
+//! files are removed first. Terms reduced by a modulus are never stored:
+//! they are small and cheap to recompute.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::cache::Key;
+
+const MAGIC: &[u8; 4] = b"FIBS";
+const VERSION: u8 = 1;
+const EXTENSION: &str = "fib";
+const TEMPORARY_EXTENSION: &str = "tmp";
+/// Terms smaller than this are quicker to recompute than to read back.
+const MIN_BITS: u64 = 1 << 16;
+
+pub struct Store {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Also serialises writes and evictions.
+    disk: Mutex<Disk>,
+}
+
+struct Disk {
+    /// Bytes currently in stored files.
+    bytes: u64,
+    /// Write sequence numbers of the files this process wrote, which order
+    /// eviction exactly where modification times may tie. Files from
+    /// earlier runs are absent and go first, oldest by modification time.
+    written: HashMap<PathBuf, u64>,
+    writes: u64,
+}
+
+impl Store {
+    /// Opens (creating if needed) a store under `dir`.
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == TEMPORARY_EXTENSION)
+            {
+                fs::remove_file(path)?;
+            }
+        }
+        let bytes = files(&dir)?.iter().map(|(_, len, _)| len).sum();
+        Ok(Self {
+            dir,
+            max_bytes,
+            disk: Mutex::new(Disk {
+                bytes,
+                written: HashMap::new(),
+                writes: 0,
+            }),
+        })
+    }
+
+    /// Returns the stored term for `key`, if any. Unreadable or corrupt
+    /// files are logged and deleted.
+    pub fn get(&self, key: &Key) -> Option<BigInt> {
+        if key.modulus.is_some() {
+            return None;
+        }
+        let path = self.path(key);
+        read(&path, key).unwrap_or_else(|err| {
+            eprintln!("store: discarding {}: {err}", path.display());
+            self.remove(&path);
+            None
+        })
+    }
+
+    /// Stores a term if it is large enough to be worth keeping. Failures
+    /// are logged; the store is only ever an optimisation.
+    pub fn put(&self, key: &Key, value: &BigInt) {
+        if key.modulus.is_some() || value.bits() < MIN_BITS {
+            return;
+        }
+        let path = self.path(key);
+        if let Err(err) = self.write(&path, key, value) {
+            eprintln!("store: could not write {}: {err}", path.display());
+        }
+    }
+
+    /// The full key is checked against the one inside the file, so a hash
+    /// collision costs a recomputation, never a wrong answer.
+    fn path(&self, key: &Key) -> PathBuf {
+        let hash = fnv1a(key_name(key).as_bytes());
+        self.dir
+            .join(format!("{}-{hash:016x}.{EXTENSION}", key.sequence))
+    }
+
+    fn write(&self, path: &Path, key: &Key, value: &BigInt) -> io::Result<()> {
+        let contents = encode(key, value);
+        let len = contents.len() as u64;
+        if len > self.max_bytes {
+            return Ok(());
+        }
+        let mut disk = self.disk.lock().unwrap_or_else(PoisonError::into_inner);
+        // Rewriting a term replaces its file rather than adding another.
+        let replaced = fs::metadata(path).map_or(0, |metadata| metadata.len());
+        let others = disk.bytes.saturating_sub(replaced);
+        disk.bytes = if others + len > self.max_bytes {
+            self.evict(&mut disk.written, path, self.max_bytes - len)?
+        } else {
+            others
+        };
+        let temporary = path.with_extension(TEMPORARY_EXTENSION);
+        fs::write(&temporary, &contents)?;
+        fs::rename(&temporary, path)?;
+        disk.bytes += len;
+        disk.writes += 1;
+        let sequence = disk.writes;
+        disk.written.insert(path.to_path_buf(), sequence);
+        Ok(())
+    }
+
+    /// Removes the least recently written files other than `keep` until at
+    /// most `target` bytes remain in them, and returns their new total.
+    fn evict(
+        &self,
+        written: &mut HashMap<PathBuf, u64>,
+        keep: &Path,
+        target: u64,
+    ) -> io::Result<u64> {
+        let mut files = files(&self.dir)?;
+        files.retain(|(path, _, _)| path != keep);
+        files.sort_by_key(|(path, _, modified)| (written.get(path).copied(), *modified));
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        for (path, len, _) in files {
+            if total <= target {
+                break;
+            }
+            fs::remove_file(&path)?;
+            written.remove(&path);
+            total -= len;
+        }
+        Ok(total)
+    }
+
+    fn remove(&self, path: &Path) {
+        let mut disk = self.disk.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Ok(metadata) = fs::metadata(path) {
+            if fs::remove_file(path).is_ok() {
+                disk.bytes = disk.bytes.saturating_sub(metadata.len());
+                disk.written.remove(path);
+            }
+        }
+    }
+}
+
+/// Stored files with their sizes and modification times.
+fn files(dir: &Path) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == EXTENSION) {
+            let metadata = fs::metadata(&path)?;
+            files.push((path, metadata.len(), metadata.modified()?));
+        }
+    }
+    Ok(files)
+}
+
+fn encode(key: &Key, value: &BigInt) -> Vec<u8> {
+    let name = key_name(key);
+    let mut body = Vec::new();
+    body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    body.extend_from_slice(name.as_bytes());
+    body.push(u8::from(value.sign() == Sign::Minus));
+    body.extend_from_slice(&value.magnitude().to_bytes_le());
+
+    let mut contents = Vec::with_capacity(body.len() + 9);
+    contents.extend_from_slice(MAGIC);
+    contents.push(VERSION);
+    contents.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    contents.extend_from_slice(&body);
+    contents
+}
+
+/// Reads the term at `path`, `Ok(None)` if there is none, or an error if
+/// the file is unreadable, from another format version, or corrupt.
+fn read(path: &Path, key: &Key) -> io::Result<Option<BigInt>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if contents.len() < 9 || &contents[..4] != MAGIC {
+        return Err(invalid("not a store file"));
+    }
+    if contents[4] != VERSION {
+        return Err(invalid("unsupported format version"));
+    }
+    let checksum = u32::from_le_bytes(contents[5..9].try_into().unwrap());
+    let body = &contents[9..];
+    if crc32fast::hash(body) != checksum {
+        return Err(invalid("checksum mismatch"));
+    }
+    let name_len = body
+        .get(..2)
+        .map(|len| u16::from_le_bytes([len[0], len[1]]) as usize)
+        .ok_or_else(|| invalid("truncated header"))?;
+    let name = body.get(2..2 + name_len);
+    if name != Some(key_name(key).as_bytes()) {
+        return Err(invalid("file holds a different key"));
+    }
+    let sign = match body.get(2 + name_len) {
+        Some(0) => Sign::Plus,
+        Some(1) => Sign::Minus,
+        _ => return Err(invalid("bad sign byte")),
+    };
+    let magnitude = BigUint::from_bytes_le(&body[3 + name_len..]);
+    Ok(Some(BigInt::from_biguint(sign, magnitude)))
+}
+
+fn key_name(key: &Key) -> String {
+    format!("{}/{}/{:?}", key.sequence, key.n, key.modulus)
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed across Rust
+/// releases, which file names that outlive the process need.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("argo-store-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn round_trips_and_detects_corruption() {
+        let dir = temp_dir("round-trip");
+        let store = Store::open(&dir, 1 << 20).unwrap();
+        let key = Key::new("fibonacci", -100_000, None);
+        let value = fib::fib(-100_000);
+        assert!(store.get(&key).is_none());
+        store.put(&key, &value);
+        // Served from disk, even by a fresh store.
+        let store = Store::open(&dir, 1 << 20).unwrap();
+        assert_eq!(store.get(&key).as_ref(), Some(&value));
+
+        let path = store.path(&key);
+        let mut contents = fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 1;
+        fs::write(&path, contents).unwrap();
+        assert!(read(&path, &key).is_err());
+        assert!(store.get(&key).is_none());
+        assert!(!path.exists());
+        store.put(&key, &value);
+        assert!(read(&path, &key).unwrap().is_some());
+
+        // Small terms are not worth a file, and modular ones never are.
+        let small = Key::new("fibonacci", 10, None);
+        store.put(&small, &55.into());
+        assert!(store.get(&small).is_none());
+        assert!(!store.path(&small).exists());
+        let index: BigInt = "9".repeat(300).parse().unwrap();
+        let modular = Key::new("fibonacci", index, Some(7));
+        store.put(&modular, &(BigInt::from(1) << MIN_BITS));
+        assert!(store.get(&modular).is_none());
+        assert!(!store.path(&modular).exists());
+
+        // File names stay short whatever the index.
+        let long = Key::new("fibonacci", BigInt::from(1) << 4096, None);
+        let value = BigInt::from(1) << MIN_BITS;
+        store.put(&long, &value);
+        assert_eq!(store.get(&long), Some(value));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn evicts_oldest_files_past_the_cap() {
+        let dir = temp_dir("evict");
+        // Room for two ~8.7 KB terms but not three.
+        let store = Store::open(&dir, 20_000).unwrap();
+        let keys: Vec<Key> = (0..3)
+            .map(|i| Key::new("fibonacci", 100_000 + i, None))
+            .collect();
+        // Written back to back, so their modification times may tie.
+        for (n, key) in (100_000..).zip(&keys) {
+            store.put(key, &fib::fib(n));
+        }
+        assert!(!store.path(&keys[0]).exists());
+        assert!(store.path(&keys[1]).exists());
+        assert!(store.path(&keys[2]).exists());
+        assert!(store.disk.lock().unwrap().bytes <= 20_000);
+
+        // Rewriting the older term makes the other one the next to go.
+        store.put(&keys[1], &fib::fib(100_001));
+        store.put(&keys[0], &fib::fib(100_000));
+        assert!(store.path(&keys[0]).exists());
+        assert!(store.path(&keys[1]).exists());
+        assert!(!store.path(&keys[2]).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rewrites_are_counted_once_and_temporaries_are_cleaned_up() {
+        let dir = temp_dir("rewrite");
+        let store = Store::open(&dir, 1 << 20).unwrap();
+        let key = Key::new("fibonacci", 100_000, None);
+        store.put(&key, &fib::fib(100_000));
+        store.put(&key, &fib::fib(100_000));
+        let len = fs::metadata(store.path(&key)).unwrap().len();
+        assert_eq!(store.disk.lock().unwrap().bytes, len);
+
+        // As left by a crash between writing and renaming.
+        let temporary = store.path(&key).with_extension(TEMPORARY_EXTENSION);
+        fs::write(&temporary, b"FIBS").unwrap();
+        let store = Store::open(&dir, 1 << 20).unwrap();
+        assert!(!temporary.exists());
+        assert_eq!(store.disk.lock().unwrap().bytes, len);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
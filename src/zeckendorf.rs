@@ -0,0 +1,86 @@
+//! Zeckendorf representations: every positive integer is a unique sum of
+//! non-consecutive Fibonacci numbers F(k), k ≥ 2.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::fib;
+
+/// Returns the Zeckendorf terms of `value` as `(k, F(k))`, largest first.
+/// Zero has no terms.
+///
+/// Greedy: repeatedly take the largest F(k) not exceeding the remainder.
+/// The walk down the sequence keeps only the pair (F(k), F(k+1)) and
+/// steps with F(k−1) = F(k+1) − F(k), so after locating the top term by
+/// fast doubling each further index costs one subtraction.
+pub fn zeckendorf(value: &BigUint) -> Vec<(u64, BigUint)> {
+    if value.is_zero() {
+        return Vec::new();
+    }
+    // Start near the top via the log-φ estimate, then settle exactly.
+    let estimate = ((fib::ln(value) + 5f64.sqrt().ln()) / fib::LN_PHI).floor();
+    let mut k = (estimate as u64).max(2);
+    let (mut a, mut b) = fib::fib_pair(k);
+    while &a > value {
+        (a, b) = (&b - &a, a);
+        k -= 1;
+    }
+    while &b <= value {
+        (a, b) = (b.clone(), a + &b);
+        k += 1;
+    }
+
+    let mut terms = Vec::new();
+    let mut remainder = value.clone();
+    while !remainder.is_zero() {
+        if a <= remainder {
+            remainder -= &a;
+            terms.push((k, a.clone()));
+        }
+        (a, b) = (&b - &a, a);
+        k -= 1;
+    }
+    terms
+}
+
+/// Fibonacci coding of a Zeckendorf index set: bit i (from the left) is set
+/// when F(i + 2) is a term, followed by a terminating `1`, so every
+/// codeword ends in `11`.
+pub fn code(terms: &[(u64, BigUint)]) -> String {
+    let Some(&(top, _)) = terms.first() else {
+        return String::new();
+    };
+    let mut bits = vec![b'0'; top as usize - 1];
+    for (k, _) in terms {
+        bits[*k as usize - 2] = b'1';
+    }
+    bits.push(b'1');
+    String::from_utf8(bits).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_are_exact_and_non_consecutive() {
+        for v in 1u32..2_000 {
+            let terms = zeckendorf(&BigUint::from(v));
+            let sum: BigUint = terms.iter().map(|(_, f)| f).sum();
+            assert_eq!(sum, BigUint::from(v));
+            for pair in terms.windows(2) {
+                assert!(pair[0].0 >= pair[1].0 + 2, "v = {v}: {terms:?}");
+            }
+            assert!(terms.iter().all(|(k, _)| *k >= 2));
+        }
+    }
+
+    #[test]
+    fn known_codes() {
+        let code_of = |v: u32| code(&zeckendorf(&BigUint::from(v)));
+        assert_eq!(code_of(1), "11");
+        assert_eq!(code_of(2), "011");
+        assert_eq!(code_of(4), "1011");
+        assert_eq!(code_of(11), "001011");
+    }
+}